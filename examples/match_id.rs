@@ -1,4 +1,4 @@
-use dronecan::Id;
+use dronecan::{Id, Kind};
 
 fn main() {
     // An example identifier
@@ -15,4 +15,10 @@ fn main() {
 
         _ => println!("Got something else..."),
     }
+
+    // Or match only the kind of transfer, regardless of node or priority
+    match id.kind() {
+        Kind::Message { type_id: 1010 } => println!("Matched kind!"),
+        _ => println!("Got another kind..."),
+    }
 }
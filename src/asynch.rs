@@ -0,0 +1,105 @@
+//! Asynchronous receive helpers.
+
+use crate::{Id, Reassembler, ReceiveError};
+use embedded_can::Frame;
+
+/// An asynchronous CAN peripheral.
+///
+/// Mirrors [`embedded_can::blocking::Can`] for the receive side.
+pub trait Can {
+    /// Associated frame type.
+    type Frame: Frame;
+
+    /// Associated error type.
+    type Error;
+
+    /// Wait for the next frame.
+    fn receive(&mut self) -> impl Future<Output = Result<Self::Frame, Self::Error>>;
+}
+
+/// Receive frames until a transfer completes.
+///
+/// Standard identifier and remote frames are ignored as they can't be part of a
+/// DroneCAN transfer.
+pub async fn receive_transfer<'r, R>(
+    rx: &mut R,
+    reassembler: &'r mut Reassembler,
+) -> Result<(Id, &'r [u8]), ReceiveError<R::Error>>
+where
+    R: Can,
+{
+    let id = loop {
+        let frame = rx.receive().await.map_err(ReceiveError::Can)?;
+
+        if let Some(id) = reassembler.push_frame(&frame)? {
+            break id;
+        }
+    };
+
+    Ok((id, reassembler.payload(id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use crate::mock::MockFrame;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use std::collections::VecDeque;
+
+    struct MockCan(VecDeque<MockFrame>);
+
+    impl Can for MockCan {
+        type Frame = MockFrame;
+        type Error = ();
+
+        async fn receive(&mut self) -> Result<Self::Frame, Self::Error> {
+            self.0.pop_front().ok_or(())
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn receive_multi() {
+        let mut rx = MockCan(VecDeque::from([
+            MockFrame::standard(0x123, &[0x00, 0xC0]),
+            MockFrame::extended(
+                0x0803F20A,
+                &[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D],
+            ),
+            MockFrame::extended(0x0803F20A, &[0x00, 0x7D, 0x33, 0x7D]),
+        ]));
+        let mut reassembler = Reassembler::new();
+
+        let res = block_on(receive_transfer(&mut rx, &mut reassembler));
+        let data = &[0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+        assert_eq!(res, Ok((Id::new(0x0803F20A), data.as_ref())));
+
+        // no frames left
+        let res = block_on(receive_transfer(&mut rx, &mut reassembler));
+        assert_eq!(res, Err(ReceiveError::Can(())));
+    }
+
+    #[test]
+    fn receive_error() {
+        let mut rx = MockCan(VecDeque::from([MockFrame::extended(
+            0x0803F20A,
+            &[0x00, 0x7D, 0x33, 0x7D],
+        )]));
+        let mut reassembler = Reassembler::new();
+
+        let res = block_on(receive_transfer(&mut rx, &mut reassembler));
+        assert_eq!(res, Err(ReceiveError::Transfer(Error::FrameOrder)));
+    }
+}
@@ -0,0 +1,124 @@
+/// CRC-16-CCITT-FALSE as used by DroneCAN.
+///
+/// Multi-frame transfers are protected by this CRC computed over the data type
+/// signature followed by the payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Crc16(u16);
+
+impl Crc16 {
+    /// Create a new CRC with the initial value.
+    pub const fn new() -> Self {
+        Self(0xFFFF)
+    }
+
+    /// Create a new transfer CRC seeded with a data type signature.
+    pub fn with_signature(signature: u64) -> Self {
+        let mut crc = Self::new();
+        crc.add(&signature.to_le_bytes());
+        crc
+    }
+
+    /// Add bytes to the CRC.
+    pub fn add(&mut self, data: &[u8]) {
+        for byte in data {
+            self.add_byte(*byte);
+        }
+    }
+
+    /// Add a single byte to the CRC.
+    ///
+    /// Computed bit by bit, or with a lookup table when the `crc-table`
+    /// feature is enabled.
+    pub fn add_byte(&mut self, byte: u8) {
+        #[cfg(feature = "crc-table")]
+        {
+            let index = ((self.0 >> 8) as u8 ^ byte) as usize;
+            self.0 = (self.0 << 8) ^ TABLE[index];
+        }
+
+        #[cfg(not(feature = "crc-table"))]
+        {
+            self.0 = add_byte_bitwise(self.0, byte);
+        }
+    }
+
+    /// Current CRC value.
+    pub fn value(&self) -> u16 {
+        self.0
+    }
+}
+
+/// Add a byte to `crc` one bit at a time.
+const fn add_byte_bitwise(mut crc: u16, byte: u8) -> u16 {
+    crc ^= (byte as u16) << 8;
+
+    let mut bit = 0;
+    while bit < 8 {
+        if crc & 0x8000 != 0 {
+            crc = (crc << 1) ^ 0x1021;
+        } else {
+            crc <<= 1;
+        }
+        bit += 1;
+    }
+
+    crc
+}
+
+/// CRC of every byte value added to zero, taking 512 bytes of flash.
+#[cfg(feature = "crc-table")]
+const TABLE: [u16; 256] = {
+    let mut table = [0; 256];
+
+    let mut i = 0;
+    while i < 256 {
+        table[i] = add_byte_bitwise(0, i as u8);
+        i += 1;
+    }
+
+    table
+};
+
+impl Default for Crc16 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_value() {
+        let mut crc = Crc16::new();
+        crc.add(b"123456789");
+        assert_eq!(crc.value(), 0x29B1);
+    }
+
+    /// The table and bitwise implementations agree on pseudo-random data.
+    #[cfg(feature = "crc-table")]
+    #[test]
+    fn table_matches_bitwise() {
+        let mut rng = crate::xorshift::XorShift::new();
+        let mut crc = Crc16::new();
+        let mut reference = 0xFFFF;
+
+        for _ in 0..4096 {
+            let byte = rng.next_u8();
+
+            crc.add_byte(byte);
+            reference = add_byte_bitwise(reference, byte);
+            assert_eq!(crc.value(), reference);
+        }
+    }
+
+    /// `uavcan.equipment.actuator.ArrayCommand` multi-frame transfer.
+    #[test]
+    fn with_signature() {
+        let mut crc = Crc16::with_signature(0xD8A7486238EC3AF3);
+        crc.add(&[0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33]);
+        assert_eq!(crc.value(), 0x9801);
+    }
+}
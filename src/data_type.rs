@@ -0,0 +1,31 @@
+/// Data type descriptor as emitted by DSDL code generators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DataType {
+    /// Data type ID.
+    pub id: u16,
+    /// Data type signature.
+    pub signature: u64,
+    /// Is this a service rather than a message?
+    pub is_service: bool,
+}
+
+impl DataType {
+    /// Describe a message data type.
+    pub const fn message(id: u16, signature: u64) -> Self {
+        Self {
+            id,
+            signature,
+            is_service: false,
+        }
+    }
+
+    /// Describe a service data type.
+    pub const fn service(id: u8, signature: u64) -> Self {
+        Self {
+            id: id as u16,
+            signature,
+            is_service: true,
+        }
+    }
+}
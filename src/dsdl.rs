@@ -0,0 +1,396 @@
+//! DSDL bit-level serialization primitives.
+//!
+//! Values are packed without padding. Bits fill each byte from the most
+//! significant bit and multi-byte values are stored little-endian, a value
+//! that doesn't fill its last byte keeps its remaining bits in the most
+//! significant bits of that byte.
+
+use core::fmt;
+
+/// DSDL decoding error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    Truncated,
+    Unaligned,
+    ArrayLength,
+    InvalidValue,
+    BufferTooSmall,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "payload is truncated"),
+            Self::Unaligned => write!(f, "field is not byte aligned"),
+            Self::ArrayLength => write!(f, "array length exceeds maximum"),
+            Self::InvalidValue => write!(f, "value is not valid for field"),
+            Self::BufferTooSmall => write!(f, "buffer is too small"),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// Convert a native float to a `float16` bit pattern.
+///
+/// Rounds to nearest, values too large for a `float16` become infinity.
+pub fn f32_to_f16(value: f32) -> u16 {
+    const F32_INF: u32 = 255 << 23;
+    const F16_INF: u32 = 31 << 23;
+    const MAGIC: u32 = 15 << 23;
+    const ROUND_MASK: u32 = !0xFFF;
+
+    let bits = value.to_bits();
+    let sign = bits & 0x8000_0000;
+    let bits = bits ^ sign;
+
+    let out = if bits >= F32_INF {
+        // NaN or infinity
+        if bits > F32_INF { 0x7FFF } else { 0x7C00 }
+    } else {
+        let scaled = f32::from_bits(bits & ROUND_MASK) * f32::from_bits(MAGIC);
+        let bits = scaled.to_bits().wrapping_sub(ROUND_MASK).min(F16_INF);
+        (bits >> 13) as u16
+    };
+
+    out | (sign >> 16) as u16
+}
+
+/// Convert a `float16` bit pattern to a native float.
+pub fn f16_to_f32(value: u16) -> f32 {
+    const MAGIC: u32 = (254 - 15) << 23;
+    const WAS_INF_NAN: u32 = (127 + 16) << 23;
+
+    let mut out = f32::from_bits((value as u32 & 0x7FFF) << 13) * f32::from_bits(MAGIC);
+    if out >= f32::from_bits(WAS_INF_NAN) {
+        out = f32::from_bits(out.to_bits() | (255 << 23));
+    }
+
+    f32::from_bits(out.to_bits() | (value as u32 & 0x8000) << 16)
+}
+
+/// Reads fields from a DSDL serialized payload.
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Create a reader starting at the first bit of `data`.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    /// Number of bits left to read.
+    pub fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.offset
+    }
+
+    /// Read an unsigned integer of up to 64 bits.
+    pub fn read_u64(&mut self, bits: u8) -> Result<u64, Error> {
+        if bits > 64 || self.remaining() < bits as usize {
+            return Err(Error::Truncated);
+        }
+
+        let mut value = 0;
+        let mut left = bits;
+        let mut shift = 0;
+
+        while left > 0 {
+            let n = left.min(8);
+            value |= (self.read_byte(n) as u64) << shift;
+            shift += 8;
+            left -= n;
+        }
+
+        Ok(value)
+    }
+
+    /// Read an unsigned integer of up to 32 bits.
+    pub fn read_u32(&mut self, bits: u8) -> Result<u32, Error> {
+        if bits > 32 {
+            return Err(Error::Truncated);
+        }
+
+        self.read_u64(bits).map(|v| v as u32)
+    }
+
+    /// Read an unsigned integer of up to 16 bits.
+    pub fn read_u16(&mut self, bits: u8) -> Result<u16, Error> {
+        if bits > 16 {
+            return Err(Error::Truncated);
+        }
+
+        self.read_u64(bits).map(|v| v as u16)
+    }
+
+    /// Read an unsigned integer of up to 8 bits.
+    pub fn read_u8(&mut self, bits: u8) -> Result<u8, Error> {
+        if bits > 8 {
+            return Err(Error::Truncated);
+        }
+
+        self.read_u64(bits).map(|v| v as u8)
+    }
+
+    /// Read a `float16` as a native float.
+    pub fn read_f16(&mut self) -> Result<f32, Error> {
+        self.read_u16(16).map(f16_to_f32)
+    }
+
+    /// Read a single bit boolean.
+    pub fn read_bool(&mut self) -> Result<bool, Error> {
+        self.read_u8(1).map(|v| v != 0)
+    }
+
+    /// Borrow `len` bytes from a byte aligned position.
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.offset % 8 != 0 {
+            return Err(Error::Unaligned);
+        }
+
+        let start = self.offset / 8;
+        let bytes = self.data.get(start..start + len).ok_or(Error::Truncated)?;
+        self.offset += len * 8;
+
+        Ok(bytes)
+    }
+
+    /// Borrow every remaining byte from a byte aligned position.
+    pub fn read_remaining_bytes(&mut self) -> Result<&'a [u8], Error> {
+        self.read_bytes(self.remaining() / 8)
+    }
+
+    /// Read a trailing variable-length array whose length prefix was omitted
+    /// by tail array optimization.
+    ///
+    /// Yields elements of `element_bits` bits until the payload is exhausted,
+    /// leftover bits too short for an element are padding. Tail array
+    /// optimization only applies to the last field of a type when its element
+    /// is at least 8 bits wide. Elements are consumed as the iterator advances.
+    pub fn read_tail_array(&mut self, element_bits: u8) -> impl Iterator<Item = u64> + '_ {
+        let count = match element_bits {
+            1..=64 => self.remaining() / element_bits as usize,
+            _ => 0,
+        };
+
+        // cannot fail, the count is limited by the remaining bits
+        (0..count).map(move |_| self.read_u64(element_bits).unwrap_or_default())
+    }
+
+    /// Read up to 8 bits, right aligned.
+    fn read_byte(&mut self, bits: u8) -> u8 {
+        let mut value = 0;
+
+        for _ in 0..bits {
+            let byte = self.data[self.offset / 8];
+            let bit = (byte >> (7 - self.offset % 8)) & 1;
+            value = (value << 1) | bit;
+            self.offset += 1;
+        }
+
+        value
+    }
+}
+
+/// Writes fields to a DSDL serialized payload.
+#[derive(Debug)]
+pub struct BitWriter<'a> {
+    data: &'a mut [u8],
+    offset: usize,
+}
+
+impl<'a> BitWriter<'a> {
+    /// Create a writer starting at the first bit of `data`.
+    pub fn new(data: &'a mut [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    /// Number of bytes written, including a partially written last byte.
+    pub fn len(&self) -> usize {
+        self.offset.div_ceil(8)
+    }
+
+    /// Has nothing been written yet?
+    pub fn is_empty(&self) -> bool {
+        self.offset == 0
+    }
+
+    /// Write the lowest `bits` bits of an unsigned integer.
+    pub fn write_u64(&mut self, value: u64, bits: u8) -> Result<(), Error> {
+        if bits > 64 || self.data.len() * 8 - self.offset < bits as usize {
+            return Err(Error::BufferTooSmall);
+        }
+
+        let mut left = bits;
+        let mut shift = 0;
+
+        while left > 0 {
+            let n = left.min(8);
+            self.write_byte((value >> shift) as u8, n);
+            shift += 8;
+            left -= n;
+        }
+
+        Ok(())
+    }
+
+    /// Write the lowest `bits` bits of an unsigned integer.
+    pub fn write_u32(&mut self, value: u32, bits: u8) -> Result<(), Error> {
+        self.write_u64(value as u64, bits.min(32))
+    }
+
+    /// Write the lowest `bits` bits of an unsigned integer.
+    pub fn write_u16(&mut self, value: u16, bits: u8) -> Result<(), Error> {
+        self.write_u64(value as u64, bits.min(16))
+    }
+
+    /// Write the lowest `bits` bits of an unsigned integer.
+    pub fn write_u8(&mut self, value: u8, bits: u8) -> Result<(), Error> {
+        self.write_u64(value as u64, bits.min(8))
+    }
+
+    /// Write a native float as a `float16`.
+    pub fn write_f16(&mut self, value: f32) -> Result<(), Error> {
+        self.write_u16(f32_to_f16(value), 16)
+    }
+
+    /// Write a single bit boolean.
+    pub fn write_bool(&mut self, value: bool) -> Result<(), Error> {
+        self.write_u8(value as u8, 1)
+    }
+
+    /// Copy bytes to a byte aligned position.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        if self.offset % 8 != 0 {
+            return Err(Error::Unaligned);
+        }
+
+        let start = self.offset / 8;
+        self.data
+            .get_mut(start..start + bytes.len())
+            .ok_or(Error::BufferTooSmall)?
+            .copy_from_slice(bytes);
+        self.offset += bytes.len() * 8;
+
+        Ok(())
+    }
+
+    /// Write the lowest `bits` bits of `value`, most significant first.
+    fn write_byte(&mut self, value: u8, bits: u8) {
+        for i in (0..bits).rev() {
+            let byte = &mut self.data[self.offset / 8];
+            let mask = 1 << (7 - self.offset % 8);
+
+            if (value >> i) & 1 != 0 {
+                *byte |= mask;
+            } else {
+                *byte &= !mask;
+            }
+
+            self.offset += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_unaligned() {
+        // uint3 = 5, uint5 = 3, uint12 = 0xABC, uint4 = 1
+        let mut reader = BitReader::new(&[0xA3, 0xBC, 0xA1]);
+        assert_eq!(reader.read_u8(3), Ok(5));
+        assert_eq!(reader.read_u8(5), Ok(3));
+        assert_eq!(reader.read_u16(12), Ok(0xABC));
+        assert_eq!(reader.read_u8(4), Ok(1));
+        assert_eq!(reader.remaining(), 0);
+        assert_eq!(reader.read_bool(), Err(Error::Truncated));
+    }
+
+    #[test]
+    fn read_little_endian() {
+        let mut reader = BitReader::new(&[0x78, 0x56, 0x34, 0x12]);
+        assert_eq!(reader.read_u32(32), Ok(0x12345678));
+    }
+
+    #[test]
+    fn read_bytes() {
+        let mut reader = BitReader::new(&[0x01, 0x02, 0x03]);
+        assert_eq!(reader.read_bool(), Ok(false));
+        assert_eq!(reader.read_bytes(1), Err(Error::Unaligned));
+        assert_eq!(reader.read_u8(7), Ok(1));
+        assert_eq!(reader.read_bytes(1), Ok([0x02].as_ref()));
+        assert_eq!(reader.read_bytes(2), Err(Error::Truncated));
+        assert_eq!(reader.read_remaining_bytes(), Ok([0x03].as_ref()));
+    }
+
+    #[test]
+    fn write_unaligned() {
+        let mut buf = [0xFF; 4];
+        let mut writer = BitWriter::new(&mut buf);
+        assert_eq!(writer.write_u8(5, 3), Ok(()));
+        assert_eq!(writer.write_u8(3, 5), Ok(()));
+        assert_eq!(writer.write_u16(0xABC, 12), Ok(()));
+        assert_eq!(writer.write_u8(1, 4), Ok(()));
+        assert_eq!(writer.write_bool(true), Ok(()));
+        assert_eq!(writer.len(), 4);
+        assert_eq!(buf, [0xA3, 0xBC, 0xA1, 0xFF]);
+    }
+
+    #[test]
+    fn write_bytes() {
+        let mut buf = [0; 3];
+        let mut writer = BitWriter::new(&mut buf);
+        assert_eq!(writer.write_u32(0x1234, 16), Ok(()));
+        assert_eq!(writer.write_bool(true), Ok(()));
+        assert_eq!(writer.write_bytes(&[0x01]), Err(Error::Unaligned));
+        assert_eq!(writer.write_u8(0, 7), Ok(()));
+        assert_eq!(writer.write_bytes(&[0x01]), Err(Error::BufferTooSmall));
+        assert_eq!(writer.write_u8(0, 1), Err(Error::BufferTooSmall));
+        assert_eq!(buf, [0x34, 0x12, 0x80]);
+    }
+
+    #[test]
+    fn read_tail_array() {
+        // uint4 then uint12[<=3], 4 bits of trailing padding
+        let mut reader = BitReader::new(&[0x5A, 0xBC, 0x12, 0x30, 0xF0]);
+        assert_eq!(reader.read_u8(4), Ok(5));
+        let array: Vec<_> = reader.read_tail_array(12).collect();
+        assert_eq!(array, [0xCAB, 0x312, 0x00F]);
+        assert_eq!(reader.remaining(), 0);
+
+        let mut reader = BitReader::new(b"\x01abc");
+        assert_eq!(reader.read_u8(8), Ok(1));
+        let text: Vec<_> = reader.read_tail_array(8).map(|b| b as u8).collect();
+        assert_eq!(text, b"abc");
+
+        assert_eq!(BitReader::new(&[0x01]).read_tail_array(0).count(), 0);
+    }
+
+    #[test]
+    fn float16() {
+        for (value, bits) in [
+            (0.0, 0x0000),
+            (-0.0, 0x8000),
+            (1.0, 0x3C00),
+            (-2.0, 0xC000),
+            (65504.0, 0x7BFF),
+            (0.000061035156, 0x0400),
+            (-0.337_890_63, 0xB568),
+            (f32::INFINITY, 0x7C00),
+            (f32::NEG_INFINITY, 0xFC00),
+        ] {
+            assert_eq!(f32_to_f16(value), bits, "{}", value);
+            assert_eq!(f16_to_f32(bits), value, "{:04x}", bits);
+        }
+
+        // rounding and overflow
+        assert_eq!(f32_to_f16(1.0004883), 0x3C01);
+        assert_eq!(f32_to_f16(1e6), 0x7C00);
+        assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+    }
+}
@@ -0,0 +1,48 @@
+/// Payload lengths of the CAN FD data length codes.
+const LENGTHS: [usize; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64];
+
+/// Convert a CAN FD data length code to a length in bytes.
+///
+/// Codes above 15 are treated as 15.
+pub fn dlc_to_len(dlc: u8) -> usize {
+    LENGTHS[dlc.min(15) as usize]
+}
+
+/// Convert a length in bytes to the smallest CAN FD data length code which
+/// fits it.
+///
+/// Lengths between the valid frame lengths round up, the frame has to be
+/// padded to [`dlc_to_len`] of the returned code. Lengths above 64 saturate to
+/// 15.
+pub fn len_to_dlc(len: usize) -> u8 {
+    LENGTHS
+        .iter()
+        .position(|&l| l >= len)
+        .unwrap_or(LENGTHS.len() - 1) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dlc_lengths() {
+        let lengths = [0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64];
+
+        for (dlc, len) in lengths.into_iter().enumerate() {
+            assert_eq!(dlc_to_len(dlc as u8), len);
+            assert_eq!(len_to_dlc(len), dlc as u8);
+        }
+
+        assert_eq!(dlc_to_len(16), 64);
+    }
+
+    #[test]
+    fn len_rounds_up() {
+        assert_eq!(len_to_dlc(9), 9);
+        assert_eq!(len_to_dlc(13), 10);
+        assert_eq!(len_to_dlc(33), 14);
+        assert_eq!(len_to_dlc(49), 15);
+        assert_eq!(len_to_dlc(65), 15);
+    }
+}
@@ -0,0 +1,279 @@
+use crate::{Id, Kind};
+
+/// Service not message bit.
+const SERVICE: u32 = 1 << 7;
+
+/// Request not response bit.
+const REQUEST: u32 = 1 << 15;
+
+/// Identifier and mask pair for a hardware CAN acceptance filter.
+///
+/// A frame is accepted when `raw & mask == id & mask`. Priority is never part
+/// of the mask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Filter {
+    id: u32,
+    mask: u32,
+}
+
+impl Filter {
+    /// Accept every transfer of a kind from any node.
+    ///
+    /// Message filters can't exclude anonymous frames as they only differ by
+    /// having a zero source node, anonymous frames whose discriminator happens
+    /// to match will also be accepted.
+    pub fn kind(kind: Kind) -> Self {
+        match kind {
+            Kind::Message { type_id } => Self {
+                id: (type_id as u32) << 8,
+                mask: 0xFFFF << 8 | SERVICE,
+            },
+            Kind::Anonymous { type_id } => Self {
+                id: (type_id as u32 & 0x3) << 8,
+                mask: 0x3 << 8 | SERVICE | 0x7F,
+            },
+            Kind::Service {
+                service_type,
+                request,
+            } => Self {
+                id: (service_type as u32) << 16 | (request as u32) << 15 | SERVICE,
+                mask: 0xFF << 16 | REQUEST | SERVICE,
+            },
+        }
+    }
+
+    /// Accept every service request or response addressed to `node`.
+    pub fn services_to(node: u8) -> Self {
+        Self {
+            id: (node as u32 & 0x7F) << 8 | SERVICE,
+            mask: 0x7F << 8 | SERVICE,
+        }
+    }
+
+    /// Accept every service request addressed to `node`.
+    pub fn service_requests_to(node: u8) -> Self {
+        Self {
+            id: REQUEST | (node as u32 & 0x7F) << 8 | SERVICE,
+            mask: REQUEST | 0x7F << 8 | SERVICE,
+        }
+    }
+
+    /// Identifier to program into the filter.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Mask to program into the filter.
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// Would the filter accept this identifier?
+    pub fn matches(&self, id: Id) -> bool {
+        id.as_raw() & self.mask == self.id & self.mask
+    }
+}
+
+/// Software subscription matching identifiers field by field.
+///
+/// Fields set to `None` match any value and priority is never compared.
+/// Unlike a [`Filter`], message subscriptions never match anonymous frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Subscription {
+    Message {
+        /// Message type ID.
+        type_id: Option<u16>,
+        /// Source node ID.
+        source_node: Option<u8>,
+    },
+    Service {
+        /// Service type ID.
+        service_type: Option<u8>,
+        /// Request `true` or response `false`.
+        request: Option<bool>,
+        /// Source node ID.
+        source_node: Option<u8>,
+        /// Destination node ID.
+        destination_node: Option<u8>,
+    },
+}
+
+impl Subscription {
+    /// Subscribe to every message of `type_id` from any node.
+    pub fn message(type_id: u16) -> Self {
+        Self::Message {
+            type_id: Some(type_id),
+            source_node: None,
+        }
+    }
+
+    /// Subscribe to every request and response of `service_type` between any
+    /// nodes.
+    pub fn service(service_type: u8) -> Self {
+        Self::Service {
+            service_type: Some(service_type),
+            request: None,
+            source_node: None,
+            destination_node: None,
+        }
+    }
+
+    /// Does the identifier match every field set in the subscription?
+    pub fn matches(&self, id: Id) -> bool {
+        fn field<T: PartialEq>(pattern: Option<T>, value: T) -> bool {
+            pattern.is_none_or(|pattern| pattern == value)
+        }
+
+        match (*self, id) {
+            (
+                Self::Message {
+                    type_id,
+                    source_node,
+                },
+                Id::Message {
+                    type_id: id_type_id,
+                    source_node: id_source_node,
+                    ..
+                },
+            ) => field(type_id, id_type_id) && field(source_node, id_source_node),
+            (
+                Self::Service {
+                    service_type,
+                    request,
+                    source_node,
+                    destination_node,
+                },
+                Id::Service {
+                    service_type: id_service_type,
+                    request: id_request,
+                    source_node: id_source_node,
+                    destination_node: id_destination_node,
+                    ..
+                },
+            ) => {
+                field(service_type, id_service_type)
+                    && field(request, id_request)
+                    && field(source_node, id_source_node)
+                    && field(destination_node, id_destination_node)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `uavcan.protocol.NodeStatus`
+    #[test]
+    fn node_status() {
+        let filter = Filter::kind(Kind::Message { type_id: 341 });
+        assert_eq!(filter.id(), 0x0001_5500);
+        assert_eq!(filter.mask(), 0x00FF_FF80);
+
+        assert!(filter.matches(Id::new(0x1001_550A)));
+        assert!(filter.matches(Id::new(0x0001_557F)));
+        assert!(!filter.matches(Id::new(0x0803F20A)));
+    }
+
+    #[test]
+    fn service_requests_to_node() {
+        let filter = Filter::service_requests_to(10);
+        assert_eq!(filter.id(), 0x0000_8A80);
+        assert_eq!(filter.mask(), 0x0000_FF80);
+
+        let request = Id::Service {
+            priority: 30,
+            service_type: 1,
+            request: true,
+            destination_node: 10,
+            source_node: 20,
+        };
+        assert!(filter.matches(request));
+        assert!(Filter::services_to(10).matches(request));
+
+        // response to the same node
+        let response = Id::Service {
+            priority: 30,
+            service_type: 1,
+            request: false,
+            destination_node: 10,
+            source_node: 20,
+        };
+        assert!(!filter.matches(response));
+        assert!(Filter::services_to(10).matches(response));
+
+        // request to another node
+        let other = Id::Service {
+            priority: 30,
+            service_type: 1,
+            request: true,
+            destination_node: 11,
+            source_node: 20,
+        };
+        assert!(!filter.matches(other));
+    }
+
+    #[test]
+    fn service_kind() {
+        let filter = Filter::kind(Kind::Service {
+            service_type: 1,
+            request: true,
+        });
+        assert!(filter.matches(Id::new(0x1E01_8A94)));
+        assert!(!filter.matches(Id::new(0x1E01_0A94))); // response
+        assert!(!filter.matches(Id::new(0x1E02_8A94))); // service type
+    }
+
+    #[test]
+    fn anonymous_kind() {
+        let filter = Filter::kind(Kind::Anonymous { type_id: 1 });
+        assert!(filter.matches(Id::new(0x104E2D00)));
+        assert!(!filter.matches(Id::new(0x104E2C00))); // type id
+        assert!(!filter.matches(Id::new(0x104E2D0A))); // not anonymous
+    }
+
+    #[test]
+    fn subscription_type_only() {
+        let subscription = Subscription::message(341);
+
+        for source_node in [1, 10, 127] {
+            let id = Id::message(source_node, 341, 16);
+            assert!(id.is_some_and(|id| subscription.matches(id)));
+        }
+
+        assert!(!subscription.matches(Id::new(0x0803F20A))); // type id
+        assert!(!subscription.matches(Id::new(0x104E2D00))); // anonymous
+        assert!(!subscription.matches(Id::new(0x1F0155FA))); // service
+    }
+
+    #[test]
+    fn subscription_fields() {
+        let subscription = Subscription::Message {
+            type_id: None,
+            source_node: Some(10),
+        };
+        assert!(subscription.matches(Id::new(0x0803F20A)));
+        assert!(subscription.matches(Id::new(0x1001_550A)));
+        assert!(!subscription.matches(Id::new(0x0001_557F)));
+
+        let request = Id::new(0x1E01_8A94);
+        let response = Id::new(0x1E01_0A94);
+        assert!(Subscription::service(1).matches(request));
+        assert!(Subscription::service(1).matches(response));
+        assert!(!Subscription::service(2).matches(request));
+
+        let subscription = Subscription::Service {
+            service_type: Some(1),
+            request: Some(true),
+            source_node: None,
+            destination_node: Some(10),
+        };
+        assert!(subscription.matches(request));
+        assert!(!subscription.matches(response));
+        assert!(!subscription.matches(Id::new(0x0803F20A)));
+    }
+}
@@ -0,0 +1,89 @@
+use core::fmt;
+
+/// Formats bytes as space separated hex without allocating.
+///
+/// ```
+/// use dronecan::HexSlice;
+///
+/// assert_eq!(format!("{}", HexSlice(&[0x01, 0xAB])), "01 ab");
+/// assert_eq!(format!("{:X}", HexSlice(&[0x01, 0xAB])), "01 AB");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexSlice<'a>(pub &'a [u8]);
+
+impl HexSlice<'_> {
+    fn write(&self, f: &mut fmt::Formatter<'_>, upper: bool) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+
+            if upper {
+                write!(f, "{:02X}", byte)?;
+            } else {
+                write!(f, "{:02x}", byte)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for HexSlice<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, false)
+    }
+}
+
+impl fmt::LowerHex for HexSlice<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, false)
+    }
+}
+
+impl fmt::UpperHex for HexSlice<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, true)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for HexSlice<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        for (i, byte) in self.0.iter().enumerate() {
+            if i > 0 {
+                defmt::write!(f, " ");
+            }
+
+            defmt::write!(f, "{=u8:02x}", byte);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format() {
+        let bytes = HexSlice(&[0x01, 0x00, 0x68, 0xB5]);
+        assert_eq!(format!("{}", bytes), "01 00 68 b5");
+        assert_eq!(format!("{:x}", bytes), "01 00 68 b5");
+        assert_eq!(format!("{:X}", bytes), "01 00 68 B5");
+        assert_eq!(format!("{}", HexSlice(&[])), "");
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn format_heapless() {
+        use core::fmt::Write;
+
+        let mut s = heapless::String::<16>::new();
+        assert!(write!(s, "{}", HexSlice(&[0x01, 0x02, 0x03])).is_ok());
+        assert_eq!(s, "01 02 03");
+
+        // doesn't fit
+        let mut s = heapless::String::<4>::new();
+        assert!(write!(s, "{}", HexSlice(&[0x01, 0x02, 0x03])).is_err());
+    }
+}
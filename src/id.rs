@@ -1,3 +1,7 @@
+use crate::{DataType, NodeId};
+use core::fmt;
+use core::str::FromStr;
+
 /// DroneCAN identifier.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -35,10 +39,56 @@ pub enum Id {
     },
 }
 
+/// Kind of transfer identified by an [`Id`] without priority or node fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Kind {
+    Message {
+        /// Message type ID.
+        type_id: u16,
+    },
+    Anonymous {
+        /// Message type ID (lower bits).
+        type_id: u8,
+    },
+    Service {
+        /// Service type ID.
+        service_type: u8,
+        /// Is the message a request?
+        request: bool,
+    },
+}
+
 impl Id {
-    /// Create a new ['Id'] from a raw identifier value.
+    /// Highest message priority (wins bus arbitration).
+    pub const HIGHEST_PRIORITY: u8 = 0;
+
+    /// Nominal message priority, halfway between highest and lowest.
+    pub const NOMINAL_PRIORITY: u8 = 16;
+
+    /// Lowest message priority.
+    pub const LOWEST_PRIORITY: u8 = 31;
+
+    /// Message type IDs reserved for vendor-specific data types.
+    ///
+    /// `0..=19999` are standard data types and `22000..` are reserved for
+    /// future use.
+    pub const VENDOR_MESSAGE_TYPE_IDS: core::ops::RangeInclusive<u16> = 20000..=21999;
+
+    /// Create a new [`Id`] from a raw identifier value.
     ///
-    /// Masked to 29 bits to ensure the id is valid.
+    /// Bits above the 29-bit extended identifier are silently discarded, so
+    /// `0xE803F20A` is the same identifier as `0x0803F20A`. A 32-bit value with
+    /// any of those bits set is likely a bug, use [`Id::new_checked`] to
+    /// reject it instead.
+    ///
+    /// All 29 bits are meaningful. Unlike UAVCAN v1 there is no reserved bit
+    /// 23, it is the most significant bit of the message type ID, service type
+    /// ID or discriminator.
+    ///
+    /// Any value is accepted, including 11-bit standard identifiers which are
+    /// never DroneCAN frames. Use [`Id::classify`] to reject values which are
+    /// unlikely to be DroneCAN identifiers.
     pub fn new(raw: u32) -> Self {
         let raw = raw & embedded_can::ExtendedId::MAX.as_raw();
 
@@ -69,6 +119,48 @@ impl Id {
         }
     }
 
+    /// Create a new [`Id`] from a raw identifier value, returning `None` if
+    /// any bit above the 29-bit extended identifier is set.
+    ///
+    /// `TryFrom<u32>` can't provide this as the masking `From<u32>` already
+    /// implies an infallible conversion.
+    pub fn new_checked(raw: u32) -> Option<Self> {
+        if raw > embedded_can::ExtendedId::MAX.as_raw() {
+            return None;
+        }
+
+        Some(Self::new(raw))
+    }
+
+    /// Create a new [`Id`] from a raw identifier value if it looks like a valid
+    /// DroneCAN identifier.
+    ///
+    /// Returns `None` when:
+    ///
+    /// - bits above the 29-bit extended identifier are set
+    /// - the value fits in 11 bits and is more likely a standard identifier
+    /// - an anonymous message has a zero discriminator
+    /// - a service has a zero source or destination node
+    pub fn classify(raw: u32) -> Option<Self> {
+        if raw > embedded_can::ExtendedId::MAX.as_raw() || raw <= 0x7FF {
+            return None;
+        }
+
+        let id = Self::new(raw);
+
+        match id {
+            Self::Anonymous {
+                discriminator: 0, ..
+            } => None,
+            Self::Service {
+                destination_node,
+                source_node,
+                ..
+            } if destination_node == 0 || source_node == 0 => None,
+            _ => Some(id),
+        }
+    }
+
     /// Create a message identifier.
     ///
     /// - `source_node` source node identifier `1..=127`
@@ -91,18 +183,97 @@ impl Id {
         })
     }
 
+    /// Create a message identifier from a validated node ID.
+    ///
+    /// Same as [`Id::message`], returns `None` for [`NodeId::BROADCAST`] as
+    /// anonymous messages are created with [`Id::anonymous`].
+    pub fn message_typed(source_node: NodeId, type_id: u16, priority: u8) -> Option<Self> {
+        Self::message(source_node.get(), type_id, priority)
+    }
+
+    /// Create a message identifier from a wider type identifier.
+    ///
+    /// Same as [`Id::message`] but returns `None` instead of truncating a
+    /// `type_id` which doesn't fit in 16 bits.
+    pub fn message_checked(source_node: u8, type_id: u32, priority: u8) -> Option<Self> {
+        let type_id = u16::try_from(type_id).ok()?;
+
+        Self::message(source_node, type_id, priority)
+    }
+
+    /// Create a vendor-specific message identifier.
+    ///
+    /// Same as [`Id::message`] but returns `None` if `type_id` is outside of
+    /// [`Id::VENDOR_MESSAGE_TYPE_IDS`], which would collide with a standard
+    /// or reserved data type.
+    pub fn vendor_message(source_node: u8, type_id: u16, priority: u8) -> Option<Self> {
+        if !Self::VENDOR_MESSAGE_TYPE_IDS.contains(&type_id) {
+            return None;
+        }
+
+        Self::message(source_node, type_id, priority)
+    }
+
+    /// Create a message identifier for a data type descriptor.
+    ///
+    /// Same as [`Id::message`] but returns `None` if `data_type` is a service.
+    pub fn message_for(data_type: &DataType, source_node: u8, priority: u8) -> Option<Self> {
+        if data_type.is_service {
+            return None;
+        }
+
+        Self::message(source_node, data_type.id, priority)
+    }
+
+    /// Create a service identifier for a data type descriptor.
+    ///
+    /// Same as [`Id::service`] but returns `None` if `data_type` is a message
+    /// or its ID doesn't fit a service type.
+    pub fn service_for(
+        data_type: &DataType,
+        source_node: u8,
+        destination_node: u8,
+        request: bool,
+        priority: u8,
+    ) -> Option<Self> {
+        if !data_type.is_service {
+            return None;
+        }
+
+        let service_type = u8::try_from(data_type.id).ok()?;
+        Self::service(
+            source_node,
+            destination_node,
+            service_type,
+            request,
+            priority,
+        )
+    }
+
+    /// Create a message identifier in a `const` context.
+    ///
+    /// Same as [`Id::message`] but values are masked to their field width
+    /// instead of being rejected. A `source_node` of zero is not checked.
+    pub const fn message_const(source_node: u8, type_id: u16, priority: u8) -> Self {
+        Self::Message {
+            priority: priority & 0x1F,
+            type_id,
+            source_node: source_node & 0x7F,
+        }
+    }
+
     /// Create an anonymous identifier.
     ///
-    /// - `type_id` message type identifier masked to the two lowest bits -
-    /// `discriminator` a field which should be filled with random data to make
-    /// anonymous frames unique. Valid values `1..=16383`
+    /// - `type_id` message type identifier masked to the two lowest bits
+    /// - `discriminator` a field which should be filled with random data to make
+    ///   anonymous frames unique. Valid values `1..=16383`
     /// - `priority` message priority `1..=31`
     pub fn anonymous(type_id: u16, discriminator: u16, priority: u8) -> Option<Self> {
         if priority > 0x1F {
             return None;
         }
 
-        if discriminator > 0x3FFF {
+        if discriminator == 0 || discriminator > 0x3FFF {
             return None;
         }
 
@@ -115,10 +286,27 @@ impl Id {
         })
     }
 
+    /// Create an anonymous identifier with a discriminator derived from the
+    /// node's unique ID.
+    ///
+    /// The discriminator is the lowest 14 bits of the CRC-16 of `unique_id`.
+    /// Returns `None` in the unlikely case that this is zero.
+    ///
+    /// - `type_id` message type identifier masked to the two lowest bits
+    /// - `unique_id` unique ID of the node
+    /// - `priority` message priority `0..=31`
+    pub fn anonymous_from_unique_id(type_id: u16, unique_id: &[u8], priority: u8) -> Option<Self> {
+        let mut crc = crate::Crc16::new();
+        crc.add(unique_id);
+
+        Self::anonymous(type_id, crc.value() & 0x3FFF, priority)
+    }
+
     /// Create a service identifier.
     ///
     /// - `source_node` source node identifier `1..=127`
-    /// - `destination_node` destination node identifier `1..=127`
+    /// - `destination_node` destination node identifier `1..=127`, other than
+    ///   `source_node`
     /// - `service_type` data type identifier of the encoded service request or response
     /// - `request` request `true` or response `false`
     /// - `priority` message priority `1..=31`
@@ -137,6 +325,16 @@ impl Id {
             return None;
         }
 
+        // services are point-to-point between nodes with an identifier
+        if source_node == 0 || destination_node == 0 {
+            return None;
+        }
+
+        // a node can't address itself
+        if source_node == destination_node {
+            return None;
+        }
+
         Some(Self::Service {
             priority,
             service_type,
@@ -146,6 +344,36 @@ impl Id {
         })
     }
 
+    /// Create a service identifier from validated node IDs.
+    ///
+    /// Same as [`Id::service`], returns `None` if either node is
+    /// [`NodeId::BROADCAST`] or both are the same node.
+    pub fn service_typed(
+        source_node: NodeId,
+        destination_node: NodeId,
+        service_type: u8,
+        request: bool,
+        priority: u8,
+    ) -> Option<Self> {
+        Self::service(
+            source_node.get(),
+            destination_node.get(),
+            service_type,
+            request,
+            priority,
+        )
+    }
+
+    /// Build a service identifier without relying on argument order.
+    pub fn service_builder() -> ServiceBuilder {
+        ServiceBuilder::default()
+    }
+
+    /// Raw 29-bit identifier value.
+    ///
+    /// Fields are masked to their width so an identifier built from a struct
+    /// literal with out of range values encodes the masked values, the same
+    /// values [`Id::new`] decodes it back to.
     pub fn as_raw(&self) -> u32 {
         let mut raw = 0_u32;
 
@@ -187,77 +415,1483 @@ impl Id {
         raw
     }
 
+    /// Worst-case number of bits a classic CAN frame with this identifier and
+    /// `data_len` bytes occupies on the bus.
+    ///
+    /// An extended frame has 54 bits subject to bit stuffing besides the data
+    /// (start of frame, 29-bit identifier, SRR, IDE, RTR, two reserved bits,
+    /// DLC and the 15-bit CRC) and 13 bits which aren't (CRC delimiter, ACK,
+    /// end of frame and interframe space). With `n = 8 * data_len`, a stuff
+    /// bit can be inserted after every 4 bits at worst:
+    ///
+    /// `n + 67 + floor((54 + n - 1) / 4)`
+    ///
+    /// `data_len` is saturated to 8 bytes.
+    pub fn frame_bit_length(&self, data_len: usize) -> usize {
+        let n = 8 * data_len.min(8);
+        n + 67 + (54 + n - 1) / 4
+    }
+
+    /// Identifier from the value of a bxCAN receive FIFO mailbox identifier
+    /// register (`CAN_RIxR`), as read by DMA or directly.
+    ///
+    /// The register holds the 29-bit extended identifier in bits 31 to 3, the
+    /// IDE bit 2 which is set for extended identifiers and the RTR bit 1 which
+    /// is set for remote frames. Bit 0 is ignored. Returns `None` for
+    /// standard identifier and remote frames as they can't be part of a
+    /// DroneCAN transfer.
+    pub fn from_bxcan_rir(rir: u32) -> Option<Self> {
+        const IDE: u32 = 1 << 2;
+        const RTR: u32 = 1 << 1;
+
+        if rir & IDE == 0 || rir & RTR != 0 {
+            return None;
+        }
+
+        Some(Self::new(rir >> 3))
+    }
+
+    /// Convert to an extended CAN identifier.
+    pub fn as_extended_id(&self) -> embedded_can::ExtendedId {
+        // `as_raw` never exceeds 29 bits so the fallback is unreachable
+        embedded_can::ExtendedId::new(self.as_raw()).unwrap_or(embedded_can::ExtendedId::ZERO)
+    }
+
+    /// Kind of transfer this identifier belongs to.
+    pub fn kind(&self) -> Kind {
+        match *self {
+            Self::Message { type_id, .. } => Kind::Message { type_id },
+            Self::Anonymous { type_id, .. } => Kind::Anonymous { type_id },
+            Self::Service {
+                service_type,
+                request,
+                ..
+            } => Kind::Service {
+                service_type,
+                request,
+            },
+        }
+    }
+
     /// Message priority.
+    ///
+    /// Masked to 5 bits, consistent with [`Id::as_raw`].
     pub fn priority(&self) -> u8 {
-        match self {
+        let priority = match self {
             Self::Message { priority, .. } => *priority,
             Self::Anonymous { priority, .. } => *priority,
             Self::Service { priority, .. } => *priority,
+        };
+
+        priority & 0x1F
+    }
+
+    /// Compare priorities where a numerically lower priority wins.
+    ///
+    /// Returns [`Ordering::Less`](core::cmp::Ordering::Less) when `self` has
+    /// a higher priority than `other`.
+    pub fn priority_cmp(&self, other: &Id) -> core::cmp::Ordering {
+        self.priority().cmp(&other.priority())
+    }
+
+    /// Compare every field except priority.
+    ///
+    /// The same transfer may be sent at different priorities, this is useful
+    /// when matching against known identifiers.
+    pub fn matches_ignoring_priority(&self, other: &Id) -> bool {
+        self.as_raw() & 0x00FF_FFFF == other.as_raw() & 0x00FF_FFFF
+    }
+
+    /// Key identifying the data type regardless of nodes, priority and
+    /// direction.
+    ///
+    /// Messages are keyed on their type ID, anonymous messages on the lowest
+    /// two bits of it that they carry. Services are keyed on the service type
+    /// with bit 16 set, so the request and response of a service share a key
+    /// which doesn't collide with a message.
+    pub fn subject_key(&self) -> u32 {
+        match *self {
+            Self::Message { type_id, .. } => type_id as u32,
+            Self::Anonymous { type_id, .. } => type_id as u32 & 0x3,
+            Self::Service { service_type, .. } => (1 << 16) | service_type as u32,
         }
     }
-}
 
-impl From<embedded_can::ExtendedId> for Id {
-    fn from(value: embedded_can::ExtendedId) -> Self {
-        Self::new(value.as_raw())
+    /// Is this a service frame whose source and destination are the same node?
+    ///
+    /// [`Id::service`] doesn't create these, but a received identifier can be
+    /// self-addressed when nodes share a node ID.
+    pub fn is_self_addressed(&self) -> bool {
+        matches!(
+            self,
+            Self::Service {
+                source_node,
+                destination_node,
+                ..
+            } if source_node == destination_node
+        )
     }
-}
 
-impl From<Id> for embedded_can::ExtendedId {
-    fn from(value: Id) -> Self {
-        Self::new(value.as_raw()).unwrap()
+    /// Is the frame addressed to every node on the bus?
+    ///
+    /// Messages and anonymous messages are broadcast, services are
+    /// point-to-point.
+    pub fn is_broadcast(&self) -> bool {
+        !matches!(self, Self::Service { .. })
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Could this anonymous identifier carry a message of `full_type_id`?
+    ///
+    /// Anonymous frames only carry the lowest two bits of the message type
+    /// ID, so only those are compared. For example every anonymous frame with
+    /// type bits `1` matches the allocation message `1` but also `5` or `9`.
+    /// Returns `false` for other kinds of identifiers.
+    pub fn anonymous_type_matches(&self, full_type_id: u16) -> bool {
+        match *self {
+            Self::Anonymous { type_id, .. } => type_id & 0x3 == (full_type_id & 0x3) as u8,
+            _ => false,
+        }
+    }
 
-    #[test]
-    fn create_ids() {
-        assert!(Id::message(123, 123, 31).is_some());
-        assert!(Id::message(123, 123, 32).is_none()); // priority too large
-        assert!(Id::anonymous(123, 123, 31).is_some());
-        assert!(Id::anonymous(123, 123, 32).is_none()); // priority too large
-        assert!(Id::service(123, 123, 123, false, 31).is_some());
-        assert!(Id::service(123, 123, 123, false, 32).is_none()); // priority too large
+    /// Discriminator of an anonymous identifier.
+    pub fn discriminator(&self) -> Option<u16> {
+        match self {
+            Self::Anonymous { discriminator, .. } => Some(*discriminator),
+            _ => None,
+        }
     }
 
-    #[test]
-    fn to_from_raw() {
-        assert_eq!(Id::new(0x0803F20A).as_raw(), 0x0803F20A); // message
-        assert_eq!(Id::new(0x184E270A).as_raw(), 0x184E270A); // message
-        assert_eq!(Id::new(0x104E2D00).as_raw(), 0x104E2D00); // anonymous
-        assert_eq!(Id::new(0x1F0155FA).as_raw(), 0x1F0155FA); // service
+    /// Node IDs referenced by this identifier.
+    ///
+    /// A message references its source, a service its source followed by its
+    /// destination. Anonymous messages reference no node.
+    #[cfg(feature = "heapless")]
+    pub fn nodes(&self) -> heapless::Vec<u8, 2> {
+        let nodes: &[u8] = match self {
+            Self::Message { source_node, .. } => &[*source_node],
+            Self::Anonymous { .. } => &[],
+            Self::Service {
+                source_node,
+                destination_node,
+                ..
+            } => &[*source_node, *destination_node],
+        };
+
+        nodes.iter().copied().collect()
     }
 
-    /// `uavcan.equipment.actuator.ArrayCommand`
+    /// Fields of a message identifier, in the argument order of
+    /// [`Id::message`]: `(source_node, type_id, priority)`.
+    pub fn as_message(&self) -> Option<(u8, u16, u8)> {
+        match *self {
+            Self::Message {
+                priority,
+                type_id,
+                source_node,
+            } => Some((source_node, type_id, priority)),
+            _ => None,
+        }
+    }
+
+    /// Fields of an anonymous identifier, in the argument order of
+    /// [`Id::anonymous`]: `(type_id, discriminator, priority)`.
+    pub fn as_anonymous(&self) -> Option<(u8, u16, u8)> {
+        match *self {
+            Self::Anonymous {
+                priority,
+                discriminator,
+                type_id,
+            } => Some((type_id, discriminator, priority)),
+            _ => None,
+        }
+    }
+
+    /// Fields of a service identifier, in the argument order of
+    /// [`Id::service`]: `(source_node, destination_node, service_type,
+    /// request, priority)`.
+    pub fn as_service(&self) -> Option<(u8, u8, u8, bool, u8)> {
+        match *self {
+            Self::Service {
+                priority,
+                service_type,
+                request,
+                destination_node,
+                source_node,
+            } => Some((
+                source_node,
+                destination_node,
+                service_type,
+                request,
+                priority,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Copy of this identifier with a different priority.
     ///
-    /// [Reference](https://dronecan.github.io/Specification/7._List_of_standard_data_types/#arraycommand)
-    #[test]
-    fn uavcan_equipment_actuator_array_command() {
-        assert_eq!(
-            Id::new(0x0803F20A),
-            Id::Message {
-                priority: 8,
-                type_id: 1010,
-                source_node: 10,
-            }
-        )
+    /// - `priority` message priority `0..=31`
+    pub fn with_priority(self, priority: u8) -> Option<Self> {
+        if priority > 0x1F {
+            return None;
+        }
+
+        let mut id = self;
+
+        match &mut id {
+            Self::Message { priority: p, .. } => *p = priority,
+            Self::Anonymous { priority: p, .. } => *p = priority,
+            Self::Service { priority: p, .. } => *p = priority,
+        }
+
+        Some(id)
     }
 
-    /// `ardupilot.indication.NotifyState`
+    /// Copy of this identifier with a different source node.
     ///
-    /// [Reference](https://dronecan.github.io/Specification/7._List_of_standard_data_types/#notifystate)
-    #[test]
-    fn ardupilot_indication_notify_state() {
-        assert_eq!(
-            Id::new(0x184E270A),
-            Id::Message {
-                priority: 24,
-                type_id: 20007,
-                source_node: 10,
-            }
-        )
+    /// - `source_node` source node identifier `1..=127`
+    ///
+    /// Returns `None` for anonymous identifiers which have no source node and
+    /// for services which would be addressed to their source.
+    pub fn remap_source(self, source_node: u8) -> Option<Self> {
+        if source_node == 0 || source_node > 0x7F {
+            return None;
+        }
+
+        let mut id = self;
+
+        match &mut id {
+            Self::Message { source_node: s, .. } => *s = source_node,
+            Self::Service { source_node: s, .. } => *s = source_node,
+            Self::Anonymous { .. } => return None,
+        }
+
+        Some(id).filter(|id| !id.is_self_addressed())
+    }
+
+    /// Copy of this identifier with a different destination node.
+    ///
+    /// - `destination_node` destination node identifier `1..=127`
+    ///
+    /// Returns `None` for message identifiers which have no destination node
+    /// and for services which would be addressed to their source.
+    pub fn remap_destination(self, destination_node: u8) -> Option<Self> {
+        if destination_node == 0 || destination_node > 0x7F {
+            return None;
+        }
+
+        match self {
+            Self::Service {
+                priority,
+                service_type,
+                request,
+                source_node,
+                ..
+            } if source_node != destination_node => Some(Self::Service {
+                priority,
+                service_type,
+                request,
+                destination_node,
+                source_node,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Response identifier for a service request.
+    ///
+    /// Source and destination nodes are swapped. Returns `None` if this
+    /// identifier is not a service request.
+    pub fn to_service_response(self) -> Option<Self> {
+        match self {
+            Self::Service {
+                priority,
+                service_type,
+                request: true,
+                destination_node,
+                source_node,
+            } => Some(Self::Service {
+                priority,
+                service_type,
+                request: false,
+                destination_node: source_node,
+                source_node: destination_node,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Response identifier and single-frame tail byte for a service request.
+    ///
+    /// Responses must reuse the transfer ID of the request they answer, the
+    /// returned tail byte carries `transfer_id` with the start and end bits
+    /// set. Multi-frame responses can pass `transfer_id` to
+    /// [`crate::Transfer::from_payload`] instead. Returns `None` if this
+    /// identifier is not a service request.
+    pub fn to_service_response_with_tail(self, transfer_id: u8) -> Option<(Self, u8)> {
+        let response = self.to_service_response()?;
+        Some((response, 0xC0 | (transfer_id & 0x1F)))
+    }
+}
+
+/// Builder for service identifiers, see [`Id::service_builder`].
+///
+/// Defaults to a request at [`Id::NOMINAL_PRIORITY`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ServiceBuilder {
+    source_node: Option<u8>,
+    destination_node: Option<u8>,
+    service_type: Option<u8>,
+    request: bool,
+    priority: u8,
+}
+
+impl Default for ServiceBuilder {
+    fn default() -> Self {
+        Self {
+            source_node: None,
+            destination_node: None,
+            service_type: None,
+            request: true,
+            priority: Id::NOMINAL_PRIORITY,
+        }
+    }
+}
+
+impl ServiceBuilder {
+    /// Source node identifier `1..=127`.
+    pub fn from(mut self, source_node: u8) -> Self {
+        self.source_node = Some(source_node);
+        self
+    }
+
+    /// Destination node identifier `1..=127`.
+    pub fn to(mut self, destination_node: u8) -> Self {
+        self.destination_node = Some(destination_node);
+        self
+    }
+
+    /// Validated source node ID, see [`ServiceBuilder::from`].
+    pub fn from_node(self, source_node: NodeId) -> Self {
+        self.from(source_node.get())
+    }
+
+    /// Validated destination node ID, see [`ServiceBuilder::to`].
+    pub fn to_node(self, destination_node: NodeId) -> Self {
+        self.to(destination_node.get())
+    }
+
+    /// Data type identifier of the service.
+    pub fn service_type(mut self, service_type: u8) -> Self {
+        self.service_type = Some(service_type);
+        self
+    }
+
+    /// Mark as a request.
+    pub fn request(mut self) -> Self {
+        self.request = true;
+        self
+    }
+
+    /// Mark as a response.
+    pub fn response(mut self) -> Self {
+        self.request = false;
+        self
+    }
+
+    /// Message priority `0..=31`.
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Create the identifier.
+    ///
+    /// Returns `None` if a node or the service type was not set, or if any
+    /// value is out of range.
+    pub fn build(self) -> Option<Id> {
+        Id::service(
+            self.source_node?,
+            self.destination_node?,
+            self.service_type?,
+            self.request,
+            self.priority,
+        )
+    }
+}
+
+/// Outstanding service request awaiting its response.
+///
+/// A response has the same service type with the source and destination nodes
+/// swapped. Priority is not compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PendingRequest {
+    response: Id,
+}
+
+impl PendingRequest {
+    /// Track a sent request, returns `None` if `request` is not a service
+    /// request identifier.
+    pub fn new(request: Id) -> Option<Self> {
+        Some(Self {
+            response: request.to_service_response()?,
+        })
+    }
+
+    /// Identifier the response is expected to have.
+    pub fn response(&self) -> Id {
+        self.response
+    }
+
+    /// Is `id` the response to this request?
+    pub fn matches_response(&self, id: Id) -> bool {
+        self.response.matches_ignoring_priority(&id)
+    }
+}
+
+impl PartialEq<u32> for Id {
+    fn eq(&self, other: &u32) -> bool {
+        self.as_raw() == *other
+    }
+}
+
+impl PartialEq<Id> for u32 {
+    fn eq(&self, other: &Id) -> bool {
+        *self == other.as_raw()
+    }
+}
+
+impl From<u32> for Id {
+    fn from(value: u32) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<Id> for u32 {
+    fn from(value: Id) -> Self {
+        value.as_raw()
+    }
+}
+
+impl From<embedded_can::ExtendedId> for Id {
+    fn from(value: embedded_can::ExtendedId) -> Self {
+        Self::new(value.as_raw())
+    }
+}
+
+impl From<Id> for embedded_can::ExtendedId {
+    fn from(value: Id) -> Self {
+        value.as_extended_id()
+    }
+}
+
+/// Named field representation of an [`Id`] for human-readable formats.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Id")]
+enum Named {
+    Message {
+        priority: u8,
+        type_id: u16,
+        source_node: u8,
+    },
+    Anonymous {
+        priority: u8,
+        discriminator: u16,
+        type_id: u8,
+    },
+    Service {
+        priority: u8,
+        service_type: u8,
+        request: bool,
+        destination_node: u8,
+        source_node: u8,
+    },
+}
+
+#[cfg(feature = "serde")]
+impl From<Id> for Named {
+    fn from(value: Id) -> Self {
+        match value {
+            Id::Message {
+                priority,
+                type_id,
+                source_node,
+            } => Self::Message {
+                priority,
+                type_id,
+                source_node,
+            },
+            Id::Anonymous {
+                priority,
+                discriminator,
+                type_id,
+            } => Self::Anonymous {
+                priority,
+                discriminator,
+                type_id,
+            },
+            Id::Service {
+                priority,
+                service_type,
+                request,
+                destination_node,
+                source_node,
+            } => Self::Service {
+                priority,
+                service_type,
+                request,
+                destination_node,
+                source_node,
+            },
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Named> for Id {
+    fn from(value: Named) -> Self {
+        match value {
+            Named::Message {
+                priority,
+                type_id,
+                source_node,
+            } => Self::Message {
+                priority,
+                type_id,
+                source_node,
+            },
+            Named::Anonymous {
+                priority,
+                discriminator,
+                type_id,
+            } => Self::Anonymous {
+                priority,
+                discriminator,
+                type_id,
+            },
+            Named::Service {
+                priority,
+                service_type,
+                request,
+                destination_node,
+                source_node,
+            } => Self::Service {
+                priority,
+                service_type,
+                request,
+                destination_node,
+                source_node,
+            },
+        }
+    }
+}
+
+/// Serialized as the variant with named fields for human-readable formats,
+/// or as the raw 29-bit value for compact formats.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Id {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serde::Serialize::serialize(&Named::from(*self), serializer)
+        } else {
+            serializer.serialize_u32(self.as_raw())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Id {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            serde::Deserialize::deserialize(deserializer).map(|named: Named| named.into())
+        } else {
+            let raw: u32 = serde::Deserialize::deserialize(deserializer)?;
+
+            if raw > embedded_can::ExtendedId::MAX.as_raw() {
+                return Err(serde::de::Error::custom("id exceeds 29 bits"));
+            }
+
+            Ok(Id::new(raw))
+        }
+    }
+}
+
+/// Error parsing an [`Id`] from a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ParseIdError;
+
+impl fmt::Display for ParseIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid id")
+    }
+}
+
+impl core::error::Error for ParseIdError {}
+
+/// Formats the identifier in the grammar accepted by [`Id::from_str`].
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Message {
+                priority,
+                type_id,
+                source_node,
+            } => write!(f, "msg:{}/{}/{}", priority, type_id, source_node),
+            Self::Anonymous {
+                priority,
+                discriminator,
+                type_id,
+            } => write!(f, "anon:{}/{}/{}", priority, type_id, discriminator),
+            Self::Service {
+                priority,
+                service_type,
+                request,
+                destination_node,
+                source_node,
+            } => write!(
+                f,
+                "svc:{}/{}/{}/{}/{}",
+                priority,
+                service_type,
+                if request { "req" } else { "resp" },
+                source_node,
+                destination_node
+            ),
+        }
+    }
+}
+
+/// Parse an identifier from a readable string.
+///
+/// Fields are decimal and separated by `/`:
+///
+/// - `msg:<priority>/<type_id>/<source_node>`
+/// - `anon:<priority>/<type_id>/<discriminator>` with `type_id` being the two
+///   lowest bits `0..=3`
+/// - `svc:<priority>/<service_type>/<req|resp>/<source_node>/<destination_node>`
+///
+/// Values are validated the same way as [`Id::message`], [`Id::anonymous`]
+/// and [`Id::service`].
+impl FromStr for Id {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, fields) = s.split_once(':').ok_or(ParseIdError)?;
+
+        match kind {
+            "msg" => {
+                let [priority, type_id, source_node] = split(fields)?;
+                Id::message(parse(source_node)?, parse(type_id)?, parse(priority)?)
+            }
+            "anon" => {
+                let [priority, type_id, discriminator] = split(fields)?;
+                let type_id = parse(type_id)?;
+                if type_id > 0x3 {
+                    return Err(ParseIdError);
+                }
+                Id::anonymous(type_id, parse(discriminator)?, parse(priority)?)
+            }
+            "svc" => {
+                let [
+                    priority,
+                    service_type,
+                    request,
+                    source_node,
+                    destination_node,
+                ] = split(fields)?;
+                let request = match request {
+                    "req" => true,
+                    "resp" => false,
+                    _ => return Err(ParseIdError),
+                };
+                Id::service(
+                    parse(source_node)?,
+                    parse(destination_node)?,
+                    parse(service_type)?,
+                    request,
+                    parse(priority)?,
+                )
+            }
+            _ => None,
+        }
+        .ok_or(ParseIdError)
+    }
+}
+
+/// Split exactly `N` `/` separated fields.
+fn split<const N: usize>(s: &str) -> Result<[&str; N], ParseIdError> {
+    let mut fields = [""; N];
+    let mut iter = s.split('/');
+
+    for field in fields.iter_mut() {
+        *field = iter.next().ok_or(ParseIdError)?;
+    }
+
+    match iter.next() {
+        Some(_) => Err(ParseIdError),
+        None => Ok(fields),
+    }
+}
+
+fn parse<T: FromStr>(s: &str) -> Result<T, ParseIdError> {
+    s.parse().map_err(|_| ParseIdError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NODE_STATUS: Id = Id::message_const(10, 341, Id::LOWEST_PRIORITY);
+
+    #[test]
+    fn create_ids() {
+        assert!(Id::message(123, 123, 31).is_some());
+        assert!(Id::message(123, 123, 32).is_none()); // priority too large
+        assert!(Id::anonymous(123, 123, 31).is_some());
+        assert!(Id::anonymous(123, 123, 32).is_none()); // priority too large
+        assert!(Id::anonymous(123, 0x4000, 31).is_none()); // discriminator too large
+        assert!(Id::service(123, 124, 123, false, 31).is_some());
+        assert!(Id::service(123, 124, 123, false, 32).is_none()); // priority too large
+        assert!(Id::service(10, 10, 123, false, 31).is_none()); // self-addressed
+        assert!(Id::service(123, 0, 123, false, 31).is_none()); // destination is zero
+        assert!(Id::service(0, 123, 123, false, 31).is_none()); // source is zero
+    }
+
+    #[test]
+    fn message_checked() {
+        assert_eq!(Id::message_checked(10, 1010, 8), Id::message(10, 1010, 8));
+        assert_eq!(
+            Id::message_checked(10, 0xFFFF, 8),
+            Id::message(10, 0xFFFF, 8)
+        );
+        assert!(Id::message_checked(10, 0x1_0000, 8).is_none());
+    }
+
+    #[test]
+    fn anonymous_zero_discriminator() {
+        assert!(Id::anonymous(1, 0, 31).is_none());
+        assert!(Id::anonymous(1, 1, 31).is_some());
+    }
+
+    #[test]
+    fn anonymous_from_unique_id() {
+        let unique_id: [u8; 16] = core::array::from_fn(|i| i as u8);
+
+        let mut crc = crate::Crc16::new();
+        crc.add(&unique_id);
+        assert_eq!(crc.value() & 0x3FFF, 0x3B37);
+
+        assert_eq!(
+            Id::anonymous_from_unique_id(1, &unique_id, 30),
+            Some(Id::Anonymous {
+                priority: 30,
+                discriminator: 0x3B37,
+                type_id: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn is_self_addressed() {
+        assert!(Id::new(0x1F017AFA).is_self_addressed());
+        assert!(!Id::new(0x1F0155FA).is_self_addressed());
+        assert!(!Id::new(0x0803F20A).is_self_addressed());
+    }
+
+    #[test]
+    fn is_broadcast() {
+        assert!(Id::new(0x0803F20A).is_broadcast());
+        assert!(Id::new(0x104E2D00).is_broadcast());
+        assert!(!Id::new(0x1F0155FA).is_broadcast());
+    }
+
+    #[test]
+    fn anonymous_type_matches() {
+        let anonymous = Id::new(0x104E2D00);
+        assert!(anonymous.anonymous_type_matches(crate::types::Allocation::TYPE_ID));
+        assert!(anonymous.anonymous_type_matches(0xFFFD));
+        assert!(!anonymous.anonymous_type_matches(2));
+
+        // only anonymous identifiers match
+        assert!(!Id::new(0x0803F20A).anonymous_type_matches(1010));
+    }
+
+    #[test]
+    fn discriminator() {
+        assert_eq!(Id::new(0x104E2D00).discriminator(), Some(0x138B));
+        assert_eq!(Id::new(0x0803F20A).discriminator(), None);
+        assert_eq!(Id::new(0x1F0155FA).discriminator(), None);
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn nodes() {
+        assert_eq!(Id::new(0x0803F20A).nodes(), [10]);
+        assert!(Id::new(0x104E2D00).nodes().is_empty());
+        assert_eq!(Id::new(0x1F0155FA).nodes(), [122, 85]);
+    }
+
+    #[test]
+    fn as_variant() {
+        let message = Id::new(0x0803F20A);
+        let anonymous = Id::new(0x104E2D00);
+        let service = Id::new(0x1F0155FA);
+
+        assert_eq!(message.as_message(), Some((10, 1010, 8)));
+        assert_eq!(anonymous.as_message(), None);
+        assert_eq!(service.as_message(), None);
+
+        assert_eq!(message.as_anonymous(), None);
+        assert_eq!(anonymous.as_anonymous(), Some((1, 0x138B, 16)));
+        assert_eq!(service.as_anonymous(), None);
+
+        assert_eq!(message.as_service(), None);
+        assert_eq!(anonymous.as_service(), None);
+        assert_eq!(service.as_service(), Some((122, 85, 1, false, 31)));
+    }
+
+    #[test]
+    fn from_bxcan_rir() {
+        assert_eq!(Id::from_bxcan_rir(0x401F9054), Some(Id::new(0x0803F20A)));
+        assert_eq!(Id::from_bxcan_rir(0x401F9055), Some(Id::new(0x0803F20A)));
+        assert_eq!(Id::from_bxcan_rir(0x401F9056), None); // remote frame
+        assert_eq!(Id::from_bxcan_rir(0x123 << 21), None); // standard id
+    }
+
+    #[test]
+    fn frame_bit_length() {
+        let id = Id::new(0x0803F20A);
+        assert_eq!(id.frame_bit_length(0), 80);
+        assert_eq!(id.frame_bit_length(1), 90);
+        assert_eq!(id.frame_bit_length(4), 120);
+        assert_eq!(id.frame_bit_length(8), 160);
+        assert_eq!(id.frame_bit_length(64), 160);
+    }
+
+    #[test]
+    fn to_from_raw() {
+        assert_eq!(Id::new(0x0803F20A).as_raw(), 0x0803F20A); // message
+        assert_eq!(Id::new(0x184E270A).as_raw(), 0x184E270A); // message
+        assert_eq!(Id::new(0x104E2D00).as_raw(), 0x104E2D00); // anonymous
+        assert_eq!(Id::new(0x1F0155FA).as_raw(), 0x1F0155FA); // service
+
+        for raw in [0x0803F20A, 0x184E270A, 0x104E2D00, 0x1F0155FA] {
+            let id: Id = raw.into();
+            assert_eq!(id, Id::new(raw));
+            assert_eq!(u32::from(id), raw);
+            let back: u32 = id.into();
+            assert_eq!(back, raw);
+        }
+    }
+
+    #[test]
+    fn new_masks() {
+        assert_eq!(Id::new(0xE803F20A), Id::new(0x0803F20A));
+        assert_eq!(Id::new(0xFFFFFFFF).as_raw(), 0x1FFFFFFF);
+        assert_eq!(Id::from(0xE803F20A), Id::new(0x0803F20A));
+
+        assert_eq!(Id::new_checked(0x0803F20A), Some(Id::new(0x0803F20A)));
+        assert_eq!(Id::new_checked(0x1FFFFFFF), Some(Id::new(0x1FFFFFFF)));
+        assert_eq!(Id::new_checked(0x2000_0000), None);
+        assert_eq!(Id::new_checked(0xE803F20A), None);
+    }
+
+    #[test]
+    fn priority_cmp() {
+        let high = Id::Message {
+            priority: Id::HIGHEST_PRIORITY,
+            type_id: 1010,
+            source_node: 10,
+        };
+        let nominal = Id::Message {
+            priority: 16,
+            type_id: 1010,
+            source_node: 10,
+        };
+        assert_eq!(high.priority_cmp(&nominal), core::cmp::Ordering::Less);
+        assert_eq!(nominal.priority_cmp(&high), core::cmp::Ordering::Greater);
+        assert_eq!(high.priority_cmp(&high), core::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn kind() {
+        assert_eq!(Id::new(0x0803F20A).kind(), Kind::Message { type_id: 1010 });
+        assert_eq!(Id::new(0x104E2D00).kind(), Kind::Anonymous { type_id: 1 });
+        assert_eq!(
+            Id::new(0x1F0155FA).kind(),
+            Kind::Service {
+                service_type: 1,
+                request: false,
+            }
+        );
+    }
+
+    #[test]
+    fn extended_id_round_trip() {
+        let id = Id::new(0x1F0155FA);
+        let extended = id.as_extended_id();
+        assert_eq!(extended.as_raw(), 0x1F0155FA);
+        assert_eq!(Id::from(extended), id);
+        assert_eq!(embedded_can::ExtendedId::from(id), extended);
+    }
+
+    #[test]
+    fn extended_id_no_panic() {
+        // cover the whole 32-bit input space including bits that get masked
+        // off
+        let mut rng = crate::xorshift::XorShift::new();
+
+        for _ in 0..100_000 {
+            let state = rng.next_u32();
+            let raw = state & embedded_can::ExtendedId::MAX.as_raw();
+            let id = Id::new(state);
+            assert_eq!(embedded_can::ExtendedId::from(id).as_raw(), raw);
+            assert_eq!(Id::from(id.as_extended_id()), id);
+        }
+    }
+
+    #[test]
+    fn with_priority() {
+        let id = Id::Message {
+            priority: 8,
+            type_id: 1010,
+            source_node: 10,
+        };
+        assert_eq!(id.with_priority(24), Id::message(10, 1010, 24));
+
+        let id = Id::Anonymous {
+            priority: 8,
+            discriminator: 123,
+            type_id: 1,
+        };
+        assert_eq!(id.with_priority(24), Id::anonymous(1, 123, 24));
+
+        let id = Id::Service {
+            priority: 8,
+            service_type: 1,
+            request: true,
+            destination_node: 20,
+            source_node: 10,
+        };
+        assert_eq!(id.with_priority(24), Id::service(10, 20, 1, true, 24));
+
+        assert!(id.with_priority(32).is_none()); // priority too large
+    }
+
+    #[test]
+    fn remap_nodes() {
+        let message = Id::Message {
+            priority: 8,
+            type_id: 1010,
+            source_node: 10,
+        };
+        assert_eq!(message.remap_source(42), Id::message(42, 1010, 8));
+        assert!(message.remap_source(0).is_none());
+        assert!(message.remap_source(128).is_none());
+        assert!(message.remap_destination(42).is_none());
+
+        let service = Id::Service {
+            priority: 30,
+            service_type: 1,
+            request: true,
+            destination_node: 20,
+            source_node: 10,
+        };
+        assert_eq!(
+            service.remap_destination(42),
+            Id::service(10, 42, 1, true, 30)
+        );
+        assert_eq!(service.remap_source(42), Id::service(42, 20, 1, true, 30));
+
+        // a node can't address itself
+        assert!(service.remap_source(20).is_none());
+        assert!(service.remap_destination(10).is_none());
+
+        let anonymous = Id::Anonymous {
+            priority: 8,
+            discriminator: 123,
+            type_id: 1,
+        };
+        assert!(anonymous.remap_source(42).is_none());
+        assert!(anonymous.remap_destination(42).is_none());
+    }
+
+    #[test]
+    fn to_service_response() {
+        let request = Id::Service {
+            priority: 30,
+            service_type: 1,
+            request: true,
+            destination_node: 20,
+            source_node: 10,
+        };
+        let response = request.to_service_response();
+        assert_eq!(
+            response,
+            Some(Id::Service {
+                priority: 30,
+                service_type: 1,
+                request: false,
+                destination_node: 10,
+                source_node: 20,
+            })
+        );
+
+        // already a response
+        assert!(response.and_then(Id::to_service_response).is_none());
+
+        let message = Id::Message {
+            priority: 8,
+            type_id: 1010,
+            source_node: 10,
+        };
+        assert!(message.to_service_response().is_none());
+    }
+
+    #[test]
+    fn to_service_response_with_tail() {
+        let request = Id::Service {
+            priority: 30,
+            service_type: 1,
+            request: true,
+            destination_node: 20,
+            source_node: 10,
+        };
+        let request_tail = 0xC0 | 17;
+
+        let response = request.to_service_response_with_tail(request_tail & 0x1F);
+        let expected = Id::Service {
+            priority: 30,
+            service_type: 1,
+            request: false,
+            destination_node: 10,
+            source_node: 20,
+        };
+        assert_eq!(response, Some((expected, 0xD1)));
+        assert_eq!(response.map(|(_, tail)| tail & 0x1F), Some(17));
+
+        // the transfer id is masked to five bits
+        let response = request.to_service_response_with_tail(0xFF);
+        assert_eq!(response.map(|(_, tail)| tail), Some(0xDF));
+
+        let message = Id::Message {
+            priority: 8,
+            type_id: 1010,
+            source_node: 10,
+        };
+        assert!(message.to_service_response_with_tail(17).is_none());
+    }
+
+    #[test]
+    fn service_builder() {
+        let request = Id::service_builder()
+            .from(10)
+            .to(20)
+            .service_type(1)
+            .build();
+        assert_eq!(request, Id::service(10, 20, 1, true, Id::NOMINAL_PRIORITY));
+
+        let response = Id::service_builder()
+            .from(20)
+            .to(10)
+            .service_type(1)
+            .response()
+            .priority(30)
+            .build();
+        assert_eq!(response, Id::service(20, 10, 1, false, 30));
+
+        // missing destination
+        assert!(
+            Id::service_builder()
+                .from(10)
+                .service_type(1)
+                .build()
+                .is_none()
+        );
+
+        // out of range
+        let builder = Id::service_builder().from(10).to(20).service_type(1);
+        assert!(builder.to(128).build().is_none());
+        assert!(builder.priority(32).build().is_none());
+
+        let (Some(a), Some(b)) = (NodeId::new(10), NodeId::new(20)) else {
+            return;
+        };
+        let typed = Id::service_builder()
+            .from_node(a)
+            .to_node(b)
+            .service_type(1)
+            .build();
+        assert_eq!(typed, request);
+    }
+
+    #[test]
+    fn typed_node_ids() {
+        let (Some(a), Some(b)) = (NodeId::new(10), NodeId::new(20)) else {
+            return;
+        };
+
+        assert_eq!(Id::message_typed(a, 1010, 8), Id::message(10, 1010, 8));
+        assert_eq!(Id::message_typed(NodeId::BROADCAST, 1010, 8), None);
+
+        assert_eq!(
+            Id::service_typed(a, b, 1, true, 8),
+            Id::service(10, 20, 1, true, 8)
+        );
+        assert_eq!(Id::service_typed(a, a, 1, true, 8), None);
+        assert_eq!(Id::service_typed(a, NodeId::BROADCAST, 1, true, 8), None);
+    }
+
+    #[test]
+    fn eq_raw() {
+        assert!(Id::new(0x0803F20A) == 0x0803F20A);
+        assert!(0x0803F20A == Id::new(0x0803F20A));
+        assert!(Id::new(0x0803F20A) != 0x0803F20B);
+        assert!(0x0803F20B != Id::new(0x0803F20A));
+    }
+
+    /// Bit 23 is not reserved in DroneCAN.
+    #[test]
+    fn bit_23() {
+        assert_eq!(
+            Id::new(0x0880_0A0A),
+            Id::Message {
+                priority: 8,
+                type_id: 0x800A,
+                source_node: 10,
+            }
+        );
+        assert_eq!(
+            Id::new(0x0880_0000),
+            Id::Anonymous {
+                priority: 8,
+                discriminator: 0x2000,
+                type_id: 0,
+            }
+        );
+        assert_eq!(
+            Id::new(0x0880_8A8B),
+            Id::Service {
+                priority: 8,
+                service_type: 0x80,
+                request: true,
+                destination_node: 10,
+                source_node: 11,
+            }
+        );
+    }
+
+    /// `uavcan.equipment.actuator.ArrayCommand`
+    ///
+    /// [Reference](https://dronecan.github.io/Specification/7._List_of_standard_data_types/#arraycommand)
+    #[test]
+    fn uavcan_equipment_actuator_array_command() {
+        assert_eq!(
+            Id::new(0x0803F20A),
+            Id::Message {
+                priority: 8,
+                type_id: 1010,
+                source_node: 10,
+            }
+        )
+    }
+
+    /// `ardupilot.indication.NotifyState`
+    ///
+    /// [Reference](https://dronecan.github.io/Specification/7._List_of_standard_data_types/#notifystate)
+    #[test]
+    fn ardupilot_indication_notify_state() {
+        assert_eq!(
+            Id::new(0x184E270A),
+            Id::Message {
+                priority: 24,
+                type_id: crate::types::ardupilot::NOTIFY_STATE,
+                source_node: 10,
+            }
+        )
+    }
+
+    #[test]
+    fn parse_display_round_trip() {
+        let message = Id::Message {
+            priority: 8,
+            type_id: 1010,
+            source_node: 10,
+        };
+        let anonymous = Id::Anonymous {
+            priority: 31,
+            discriminator: 0x1234,
+            type_id: 1,
+        };
+        let service = Id::Service {
+            priority: 30,
+            service_type: 1,
+            request: true,
+            destination_node: 125,
+            source_node: 10,
+        };
+        let response = service.to_service_response();
+
+        assert_eq!(message.to_string(), "msg:8/1010/10");
+        assert_eq!(anonymous.to_string(), "anon:31/1/4660");
+        assert_eq!(service.to_string(), "svc:30/1/req/10/125");
+        assert_eq!(
+            response.map(|id| id.to_string()).as_deref(),
+            Some("svc:30/1/resp/125/10")
+        );
+
+        for id in [Some(message), Some(anonymous), Some(service), response]
+            .into_iter()
+            .flatten()
+        {
+            assert_eq!(id.to_string().parse(), Ok(id));
+        }
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for s in [
+            "",
+            "msg",
+            "msg:8/1010",
+            "msg:8/1010/10/1",
+            "msg:32/1010/10",
+            "msg:8/65536/10",
+            "msg:8/1010/0",
+            "msg:8/1010/x",
+            "anon:8/4/1",
+            "anon:8/1/0",
+            "svc:30/1/request/10/125",
+            "svc:30/1/req/10/250",
+            "foo:8/1010/10",
+        ] {
+            assert_eq!(s.parse::<Id>(), Err(ParseIdError), "{}", s);
+        }
+    }
+
+    #[test]
+    fn message_const() {
+        assert_eq!(Some(NODE_STATUS), Id::message(10, 341, 31));
+
+        // out of range values are masked
+        let id = Id::message_const(0xFF, 341, 0xFF);
+        assert_eq!(id.priority(), 31);
+        assert_eq!(id, Id::message_const(0x7F, 341, 31));
+    }
+
+    #[test]
+    fn out_of_range_priority() {
+        let id = Id::Message {
+            priority: 40,
+            type_id: 1010,
+            source_node: 10,
+        };
+
+        assert_eq!(id.priority(), 8);
+
+        let decoded = Id::new(id.as_raw());
+        assert_eq!(decoded.priority(), id.priority());
+        assert_eq!(decoded.as_raw(), id.as_raw());
+        assert_eq!(Id::new(decoded.as_raw()), decoded);
+    }
+
+    #[test]
+    fn classify() {
+        assert_eq!(Id::classify(0x0803F20A), Some(Id::new(0x0803F20A))); // message
+        assert_eq!(Id::classify(0x104E2D00), Some(Id::new(0x104E2D00))); // anonymous
+        assert_eq!(Id::classify(0x1F0155FA), Some(Id::new(0x1F0155FA))); // service
+
+        assert_eq!(Id::classify(0), None);
+        assert_eq!(Id::classify(0x123), None); // standard id
+        assert_eq!(Id::classify(0x7FF), None); // standard id
+        assert_eq!(Id::classify(0x2803F20A), None); // bit 29 set
+        assert_eq!(Id::classify(0xFFFF_FFFF), None);
+        assert_eq!(Id::classify(0x10000100), None); // zero discriminator
+        assert_eq!(Id::classify(0x1F01D580), None); // service from node 0
+        assert_eq!(Id::classify(0x1F0180FA), None); // service to node 0
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_json() {
+        let message = Id::Message {
+            priority: 8,
+            type_id: 1010,
+            source_node: 10,
+        };
+        let json = serde_json::to_string(&message).ok();
+        assert_eq!(
+            json.as_deref(),
+            Some(r#"{"Message":{"priority":8,"type_id":1010,"source_node":10}}"#)
+        );
+
+        for raw in [0x0803F20A, 0x104E2D00, 0x1F0155FA] {
+            let id = Id::new(raw);
+            let json = serde_json::to_string(&id).unwrap_or_default();
+            assert_eq!(serde_json::from_str::<Id>(&json).ok(), Some(id));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_postcard() {
+        let mut buf = [0; 8];
+
+        for raw in [0x0803F20A, 0x104E2D00, 0x1F0155FA] {
+            let id = Id::new(raw);
+            let bytes = postcard::to_slice(&id, &mut buf).map(|b| b.to_vec());
+            let bytes = bytes.unwrap_or_default();
+
+            // varint encoded raw value
+            assert!(bytes.len() <= 5);
+            assert_eq!(postcard::from_bytes::<Id>(&bytes).ok(), Some(id));
+        }
+
+        let bytes = postcard::to_slice(&u32::MAX, &mut buf).map(|b| b.to_vec());
+        let bytes = bytes.unwrap_or_default();
+        assert!(postcard::from_bytes::<Id>(&bytes).is_err());
+    }
+
+    #[test]
+    fn vendor_message() {
+        assert_eq!(Id::vendor_message(10, 19999, 16), None);
+        assert_eq!(
+            Id::vendor_message(10, 20000, 16),
+            Id::message(10, 20000, 16)
+        );
+        assert_eq!(
+            Id::vendor_message(10, 21999, 16),
+            Id::message(10, 21999, 16)
+        );
+        assert_eq!(Id::vendor_message(10, 22000, 16), None);
+        assert_eq!(Id::vendor_message(0, 20000, 16), None);
+    }
+
+    #[test]
+    fn matches_ignoring_priority() {
+        let nominal = Id::Message {
+            priority: Id::NOMINAL_PRIORITY,
+            type_id: 1010,
+            source_node: 10,
+        };
+        let lowest = Id::Message {
+            priority: Id::LOWEST_PRIORITY,
+            type_id: 1010,
+            source_node: 10,
+        };
+        let other = Id::Message {
+            priority: Id::NOMINAL_PRIORITY,
+            type_id: 1010,
+            source_node: 11,
+        };
+
+        assert_ne!(nominal, lowest);
+        assert!(nominal.matches_ignoring_priority(&lowest));
+        assert!(lowest.matches_ignoring_priority(&nominal));
+        assert!(!nominal.matches_ignoring_priority(&other));
+
+        let request = Id::new(0x1F0195FA);
+        let response = Id::new(0x1E0115FA);
+        assert!(!request.matches_ignoring_priority(&response));
+        assert!(request.matches_ignoring_priority(&Id::new(0x000195FA)));
+    }
+
+    #[test]
+    fn pending_request() {
+        let request = Id::service_builder()
+            .from(10)
+            .to(20)
+            .service_type(1)
+            .request()
+            .build();
+        let pending = request.and_then(PendingRequest::new);
+        let response = Id::service_builder()
+            .from(20)
+            .to(10)
+            .service_type(1)
+            .response()
+            .priority(Id::LOWEST_PRIORITY)
+            .build();
+
+        assert_eq!(
+            pending.map(|p| p.response()),
+            request.and_then(|r| r.to_service_response())
+        );
+        assert!(
+            pending
+                .zip(response)
+                .is_some_and(|(p, r)| p.matches_response(r))
+        );
+
+        // request instead of response, wrong node and wrong service type
+        let mismatched = [
+            request,
+            Id::service_builder()
+                .from(21)
+                .to(10)
+                .service_type(1)
+                .response()
+                .build(),
+            Id::service_builder()
+                .from(20)
+                .to(10)
+                .service_type(2)
+                .response()
+                .build(),
+        ];
+        for id in mismatched.into_iter().flatten() {
+            assert!(pending.is_some_and(|p| !p.matches_response(id)));
+        }
+
+        assert_eq!(response.and_then(PendingRequest::new), None);
+        assert_eq!(PendingRequest::new(Id::new(0x0803F20A)), None);
+    }
+
+    #[test]
+    fn id_for_data_type() {
+        let node_status = DataType::message(341, 0x0F0868D0C1A7C6F1);
+        let get_node_info = DataType::service(1, 0xEE468A8121C46A9E);
+
+        assert_eq!(
+            Id::message_for(&node_status, 10, 16),
+            Id::message(10, 341, 16)
+        );
+        assert_eq!(Id::message_for(&get_node_info, 10, 16), None);
+
+        assert_eq!(
+            Id::service_for(&get_node_info, 10, 20, true, 30),
+            Id::service(10, 20, 1, true, 30)
+        );
+        assert_eq!(Id::service_for(&node_status, 10, 20, true, 30), None);
+    }
+
+    #[test]
+    fn subject_key() {
+        let request = Id::new(0x1F0195FA);
+        let response = request.to_service_response();
+        assert_eq!(
+            response.map(|r| r.subject_key()),
+            Some(request.subject_key())
+        );
+        assert_eq!(request.subject_key(), 0x10001);
+
+        let message = Id::new(0x0803F20A);
+        assert_eq!(message.subject_key(), 1010);
+        assert_eq!(
+            message.remap_source(20).map(|m| m.subject_key()),
+            Some(1010)
+        );
+
+        // message with the same type ID as the service type
+        let message = Id::Message {
+            priority: 16,
+            type_id: 1,
+            source_node: 10,
+        };
+        assert_ne!(message.subject_key(), request.subject_key());
     }
 }
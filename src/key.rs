@@ -0,0 +1,15 @@
+use crate::Id;
+
+/// Identifies the transfer a frame belongs to.
+///
+/// Messages are grouped by source node and data type. Services are grouped by
+/// source node, destination node, data type and request/response so a request
+/// and its response never collide. Priority is ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Key(u32);
+
+impl Key {
+    pub(crate) fn new(id: Id) -> Self {
+        Self(id.as_raw() & 0x00FF_FFFF)
+    }
+}
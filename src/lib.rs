@@ -2,8 +2,49 @@
 #![cfg_attr(not(test), no_std)]
 #![deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "async")]
+pub mod asynch;
+mod crc;
+mod data_type;
+pub mod dsdl;
+mod fd;
+mod filter;
+mod hex;
 mod id;
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+mod key;
+#[cfg(all(test, feature = "alloc"))]
+mod mock;
+mod node_id;
+#[cfg(feature = "heapless")]
+mod pool;
+#[cfg(feature = "heapless")]
+mod queue;
+#[cfg(feature = "alloc")]
+mod reassembler;
 mod transfer;
+mod transfer_id;
+pub mod types;
+mod wire;
+#[cfg(test)]
+mod xorshift;
 
+pub use crc::*;
+pub use data_type::*;
+pub use fd::*;
+pub use filter::*;
+pub use hex::*;
 pub use id::*;
+pub use node_id::*;
+#[cfg(feature = "heapless")]
+pub use pool::*;
+#[cfg(feature = "heapless")]
+pub use queue::*;
+#[cfg(feature = "alloc")]
+pub use reassembler::*;
 pub use transfer::*;
+pub use transfer_id::*;
+pub use wire::*;
@@ -0,0 +1,107 @@
+//! Test doubles for HAL types.
+
+/// Minimal [`embedded_can::Frame`] implementation.
+#[derive(Debug, Clone)]
+pub struct MockFrame {
+    id: embedded_can::Id,
+    remote: bool,
+    data: Vec<u8>,
+}
+
+impl MockFrame {
+    pub fn extended(id: u32, data: &[u8]) -> Self {
+        Self {
+            id: embedded_can::Id::Extended(crate::Id::new(id).into()),
+            remote: false,
+            data: data.to_vec(),
+        }
+    }
+
+    pub fn remote(id: u32, dlc: usize) -> Self {
+        Self {
+            id: embedded_can::Id::Extended(crate::Id::new(id).into()),
+            remote: true,
+            data: vec![0; dlc],
+        }
+    }
+
+    pub fn standard(id: u16, data: &[u8]) -> Self {
+        let id = embedded_can::StandardId::new(id).unwrap_or(embedded_can::StandardId::ZERO);
+
+        Self {
+            id: embedded_can::Id::Standard(id),
+            remote: false,
+            data: data.to_vec(),
+        }
+    }
+}
+
+impl embedded_can::Frame for MockFrame {
+    fn new(id: impl Into<embedded_can::Id>, data: &[u8]) -> Option<Self> {
+        Some(Self {
+            id: id.into(),
+            remote: false,
+            data: data.to_vec(),
+        })
+    }
+
+    fn new_remote(id: impl Into<embedded_can::Id>, dlc: usize) -> Option<Self> {
+        Some(Self {
+            id: id.into(),
+            remote: true,
+            data: vec![0; dlc],
+        })
+    }
+
+    fn is_extended(&self) -> bool {
+        matches!(self.id, embedded_can::Id::Extended(_))
+    }
+
+    fn is_remote_frame(&self) -> bool {
+        self.remote
+    }
+
+    fn id(&self) -> embedded_can::Id {
+        self.id
+    }
+
+    fn dlc(&self) -> usize {
+        self.data.len()
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// Blocking [`embedded_can::blocking::Can`] yielding queued frames.
+///
+/// Returns [`embedded_can::ErrorKind::Other`] once the queue is empty.
+#[derive(Debug, Default)]
+pub struct MockCan {
+    pub rx: std::collections::VecDeque<MockFrame>,
+    pub tx: Vec<MockFrame>,
+}
+
+impl MockCan {
+    pub fn new(rx: impl IntoIterator<Item = MockFrame>) -> Self {
+        Self {
+            rx: rx.into_iter().collect(),
+            tx: Vec::new(),
+        }
+    }
+}
+
+impl embedded_can::blocking::Can for MockCan {
+    type Frame = MockFrame;
+    type Error = embedded_can::ErrorKind;
+
+    fn transmit(&mut self, frame: &Self::Frame) -> Result<(), Self::Error> {
+        self.tx.push(frame.clone());
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<Self::Frame, Self::Error> {
+        self.rx.pop_front().ok_or(embedded_can::ErrorKind::Other)
+    }
+}
@@ -0,0 +1,93 @@
+use core::fmt;
+
+/// Node identifier, validated to `1..=127`.
+///
+/// Zero is reserved for anonymous nodes and as the broadcast destination, it
+/// is only available as [`NodeId::BROADCAST`]. Accepted by
+/// [`crate::Id::message_typed`], [`crate::Id::service_typed`] and
+/// [`crate::ServiceBuilder::from_node`], and converts into the `u8` expected
+/// by the other [`crate::Id`] constructors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NodeId(u8);
+
+impl NodeId {
+    /// Node ID of anonymous nodes and broadcast destination.
+    pub const BROADCAST: NodeId = NodeId(0);
+
+    /// Lowest unicast node ID.
+    pub const MIN: NodeId = NodeId(1);
+
+    /// Highest unicast node ID.
+    pub const MAX: NodeId = NodeId(127);
+
+    /// Create a node ID, returning `None` outside of `1..=127`.
+    pub const fn new(value: u8) -> Option<Self> {
+        if value == 0 || value > 0x7F {
+            None
+        } else {
+            Some(Self(value))
+        }
+    }
+
+    /// Raw node ID value.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    /// Is this the anonymous or broadcast node ID?
+    pub const fn is_broadcast(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl TryFrom<u8> for NodeId {
+    type Error = InvalidNodeId;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value).ok_or(InvalidNodeId)
+    }
+}
+
+impl From<NodeId> for u8 {
+    fn from(value: NodeId) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Error converting a value outside of `1..=127` into a [`NodeId`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InvalidNodeId;
+
+impl fmt::Display for InvalidNodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid node id")
+    }
+}
+
+impl core::error::Error for InvalidNodeId {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range() {
+        assert_eq!(NodeId::try_from(0), Err(InvalidNodeId));
+        assert_eq!(NodeId::try_from(1), Ok(NodeId::MIN));
+        assert_eq!(NodeId::try_from(127), Ok(NodeId::MAX));
+        assert_eq!(NodeId::try_from(128), Err(InvalidNodeId));
+        assert_eq!(NodeId::try_from(255), Err(InvalidNodeId));
+
+        assert_eq!(NodeId::new(42).map(u8::from), Some(42));
+        assert!(NodeId::BROADCAST.is_broadcast());
+        assert!(!NodeId::MIN.is_broadcast());
+    }
+}
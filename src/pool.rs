@@ -0,0 +1,185 @@
+use crate::key::Key;
+use crate::transfer::Tail;
+use crate::{Error, Id, Transfer};
+
+/// Fixed-size pool of concurrent transfers which doesn't require an allocator.
+///
+/// Up to `N` transfers are tracked at once, each with a `BUF` byte payload
+/// buffer. When a frame arrives for a new transfer and every slot is in use,
+/// the least recently used completed transfer is recycled. Only a start frame
+/// takes over the least recently used ongoing transfer, other frames are
+/// rejected with [`Error::FrameOrder`].
+#[derive(Debug)]
+pub struct TransferPool<const N: usize, const BUF: usize> {
+    slots: [Option<Slot<BUF>>; N],
+    tick: u32,
+}
+
+#[derive(Debug)]
+struct Slot<const BUF: usize> {
+    key: Key,
+    transfer: Transfer<'static, BUF>,
+    complete: bool,
+    last_used: u32,
+}
+
+impl<const BUF: usize> Slot<BUF> {
+    fn new(key: Key, tick: u32) -> Self {
+        Self {
+            key,
+            transfer: Transfer::new(heapless::Vec::new()),
+            complete: false,
+            last_used: tick,
+        }
+    }
+}
+
+impl<const N: usize, const BUF: usize> TransferPool<N, BUF> {
+    /// Create a new pool with every slot free.
+    pub fn new() -> Self {
+        Self {
+            slots: core::array::from_fn(|_| None),
+            tick: 0,
+        }
+    }
+
+    /// Feed a data frame to the transfer it belongs to.
+    ///
+    /// Returns `Ok(Some(&[u8]))` with the payload when the frame completes a
+    /// transfer. A transfer which fails is dropped so the next start frame can
+    /// begin a new one.
+    #[must_use = "the payload of a completed transfer is only returned once"]
+    pub fn feed(&mut self, id: Id, data: &[u8]) -> Result<Option<&[u8]>, Error> {
+        self.tick = self.tick.wrapping_add(1);
+        let tick = self.tick;
+
+        let start = data.last().is_some_and(|tail| Tail::new(*tail).start());
+        let index = self.slot(Key::new(id), start)?;
+        let Some(slot) = &mut self.slots[index] else {
+            return Err(Error::BufferTooSmall);
+        };
+
+        if slot.complete {
+            *slot = Slot::new(slot.key, tick);
+        }
+        slot.last_used = tick;
+
+        match slot.transfer.feed(None, data) {
+            Ok(true) => slot.complete = true,
+            Ok(false) => return Ok(None),
+            Err(err) => {
+                self.slots[index] = None;
+                return Err(err);
+            }
+        }
+
+        Ok(self.slots[index].as_ref().map(|slot| slot.transfer.data()))
+    }
+
+    /// Find the index of the slot for `key`, claiming a free one, the least
+    /// recently used completed one or, for a `start` frame, the least recently
+    /// used ongoing one if the transfer isn't tracked yet.
+    fn slot(&mut self, key: Key, start: bool) -> Result<usize, Error> {
+        let mut free = None;
+        let mut oldest_complete: Option<(usize, u32)> = None;
+        let mut oldest: Option<(usize, u32)> = None;
+
+        for (index, slot) in self.slots.iter().enumerate() {
+            match slot {
+                Some(slot) if slot.key == key => return Ok(index),
+                Some(slot) => {
+                    let age = self.tick.wrapping_sub(slot.last_used);
+                    let oldest = if slot.complete {
+                        &mut oldest_complete
+                    } else {
+                        &mut oldest
+                    };
+                    if oldest.is_none_or(|(_, oldest)| age > oldest) {
+                        *oldest = Some((index, age));
+                    }
+                }
+                None => {
+                    free = free.or(Some(index));
+                }
+            }
+        }
+
+        let index = match free.or(oldest_complete.map(|(index, _)| index)) {
+            Some(index) => index,
+            None if self.slots.is_empty() => return Err(Error::BufferTooSmall),
+            None if start => oldest.ok_or(Error::BufferTooSmall)?.0,
+            None => return Err(Error::FrameOrder),
+        };
+
+        self.slots[index] = Some(Slot::new(key, self.tick));
+        Ok(index)
+    }
+}
+
+impl<const N: usize, const BUF: usize> Default for TransferPool<N, BUF> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: [u8; 8] = [0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D];
+    const END: [u8; 4] = [0x00, 0x7D, 0x33, 0x7D];
+    const PAYLOAD: [u8; 8] = [0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+
+    #[test]
+    fn interleaved_sources() {
+        let mut pool = TransferPool::<2, 8>::new();
+        let a = Id::new(0x0803F20A);
+        let b = Id::new(0x0803F20B);
+
+        assert_eq!(pool.feed(a, &START), Ok(None));
+        assert_eq!(pool.feed(b, &START), Ok(None));
+        assert_eq!(pool.feed(a, &END), Ok(Some(PAYLOAD.as_ref())));
+        assert_eq!(pool.feed(b, &END), Ok(Some(PAYLOAD.as_ref())));
+
+        // completed transfers make way for new ones
+        assert_eq!(pool.feed(a, &START), Ok(None));
+        assert_eq!(pool.feed(a, &END), Ok(Some(PAYLOAD.as_ref())));
+    }
+
+    #[test]
+    fn lru_eviction() {
+        let mut pool = TransferPool::<2, 8>::new();
+        let a = Id::new(0x0803F20A);
+        let b = Id::new(0x0803F20B);
+        let c = Id::new(0x0803F20C);
+
+        assert_eq!(pool.feed(a, &START), Ok(None));
+        assert_eq!(pool.feed(b, &START), Ok(None));
+        assert_eq!(pool.feed(a, &END), Ok(Some(PAYLOAD.as_ref())));
+
+        // all slots in use, the completed transfer of a makes way
+        assert_eq!(pool.feed(c, &START), Ok(None));
+        assert_eq!(pool.feed(b, &END), Ok(Some(PAYLOAD.as_ref())));
+        assert_eq!(pool.feed(c, &END), Ok(Some(PAYLOAD.as_ref())));
+
+        // stray frames never take over an ongoing transfer
+        assert_eq!(pool.feed(a, &START), Ok(None));
+        assert_eq!(pool.feed(b, &START), Ok(None));
+        assert_eq!(pool.feed(c, &END), Err(Error::FrameOrder));
+
+        // start frames take over the least recently used one
+        assert_eq!(pool.feed(c, &START), Ok(None));
+        assert_eq!(pool.feed(a, &END), Err(Error::FrameOrder));
+        assert_eq!(pool.feed(b, &END), Ok(Some(PAYLOAD.as_ref())));
+        assert_eq!(pool.feed(c, &END), Ok(Some(PAYLOAD.as_ref())));
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let mut pool = TransferPool::<1, 7>::new();
+        let a = Id::new(0x0803F20A);
+
+        assert_eq!(pool.feed(a, &START), Ok(None));
+        assert_eq!(pool.feed(a, &END), Err(Error::BufferTooSmall));
+    }
+}
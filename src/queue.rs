@@ -0,0 +1,111 @@
+use crate::{Error, Id};
+
+/// Fixed-size outbox of transfers ordered by bus arbitration.
+///
+/// Up to `N` payloads of at most `BUF` bytes are queued. The identifier which
+/// would win CAN arbitration, the numerically lowest raw value, is dequeued
+/// first and equal identifiers are dequeued in the order they were pushed.
+#[derive(Debug)]
+pub struct TxQueue<const N: usize, const BUF: usize> {
+    entries: heapless::Vec<(Id, heapless::Vec<u8, BUF>), N>,
+}
+
+impl<const N: usize, const BUF: usize> TxQueue<N, BUF> {
+    /// Create a new empty queue.
+    pub const fn new() -> Self {
+        Self {
+            entries: heapless::Vec::new(),
+        }
+    }
+
+    /// Queue a payload for transmission.
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the queue is full or the payload
+    /// is longer than `BUF` bytes.
+    pub fn push(&mut self, id: Id, payload: &[u8]) -> Result<(), Error> {
+        let payload = heapless::Vec::from_slice(payload).map_err(|_| Error::BufferTooSmall)?;
+
+        self.entries
+            .push((id, payload))
+            .map_err(|_| Error::BufferTooSmall)
+    }
+
+    /// Dequeue the payload with the highest arbitration priority.
+    pub fn pop_highest_priority(&mut self) -> Option<(Id, heapless::Vec<u8, BUF>)> {
+        let index = self
+            .entries
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (id, _))| id.as_raw())
+            .map(|(i, _)| i)?;
+
+        Some(self.entries.remove(index))
+    }
+
+    /// Number of queued payloads.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Is the queue empty?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<const N: usize, const BUF: usize> Default for TxQueue<N, BUF> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(priority: u8, source_node: u8) -> Id {
+        Id::Message {
+            priority,
+            type_id: 1010,
+            source_node,
+        }
+    }
+
+    #[test]
+    fn pop_order() {
+        let mut queue = TxQueue::<4, 8>::new();
+        assert_eq!(queue.push(message(16, 10), &[0x01]), Ok(()));
+        assert_eq!(queue.push(message(31, 10), &[0x02]), Ok(()));
+        assert_eq!(queue.push(message(0, 10), &[0x03]), Ok(()));
+        assert_eq!(queue.push(message(16, 10), &[0x04]), Ok(()));
+        assert_eq!(
+            queue.push(message(0, 10), &[0x05]),
+            Err(Error::BufferTooSmall)
+        );
+        assert_eq!(queue.len(), 4);
+
+        let order: Vec<_> = core::iter::from_fn(|| queue.pop_highest_priority())
+            .map(|(id, payload)| (id.priority(), payload[0]))
+            .collect();
+        assert_eq!(order, [(0, 0x03), (16, 0x01), (16, 0x04), (31, 0x02)]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn arbitration_tie_break() {
+        // same priority, lower source node wins arbitration
+        let mut queue = TxQueue::<2, 8>::new();
+        assert_eq!(queue.push(message(16, 20), &[0x01]), Ok(()));
+        assert_eq!(queue.push(message(16, 10), &[0x02]), Ok(()));
+        assert_eq!(
+            queue.pop_highest_priority().map(|(id, _)| id),
+            Some(message(16, 10))
+        );
+
+        let mut queue = TxQueue::<1, 2>::new();
+        assert_eq!(
+            queue.push(message(16, 20), &[0; 3]),
+            Err(Error::BufferTooSmall)
+        );
+    }
+}
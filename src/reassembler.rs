@@ -0,0 +1,680 @@
+use crate::key::Key;
+use crate::transfer::Tail;
+use crate::{Error, Id, Transfer};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt;
+use embedded_can::Frame;
+
+/// Reassembles concurrent transfers from different sources and data types.
+///
+/// Each transfer is tracked separately with owned storage. A transfer which
+/// fails is dropped so the next start frame can begin a new one.
+#[derive(Debug)]
+pub struct Reassembler {
+    slots: BTreeMap<Key, Slot>,
+    max_slots: usize,
+    tick: u32,
+    check_transfer_id: bool,
+    strict_priority: bool,
+    drop_self_addressed: bool,
+}
+
+#[derive(Debug)]
+struct Slot {
+    transfer: Transfer<'static>,
+    complete: bool,
+    priority: Option<u8>,
+    last_transfer_id: Option<u8>,
+    gap: Option<Error>,
+    last_used: u32,
+}
+
+impl Slot {
+    fn new(tick: u32) -> Self {
+        Self {
+            transfer: Transfer::new(Vec::new()),
+            complete: false,
+            priority: None,
+            last_transfer_id: None,
+            gap: None,
+            last_used: tick,
+        }
+    }
+}
+
+impl Slot {
+    /// Discard the ongoing transfer, keeping the transfer ID history.
+    fn reset(&mut self) {
+        self.transfer = Transfer::new(Vec::new());
+        self.complete = false;
+        self.priority = None;
+        self.gap = None;
+    }
+}
+
+impl Default for Reassembler {
+    fn default() -> Self {
+        Self {
+            slots: BTreeMap::new(),
+            max_slots: Self::DEFAULT_MAX_SLOTS,
+            tick: 0,
+            check_transfer_id: false,
+            strict_priority: false,
+            drop_self_addressed: false,
+        }
+    }
+}
+
+impl Reassembler {
+    /// Default limit of [`Reassembler::with_max_slots`].
+    pub const DEFAULT_MAX_SLOTS: usize = 64;
+
+    /// Create a new reassembler with no ongoing transfers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of transfers tracked at once.
+    ///
+    /// When a frame arrives for a new transfer and the limit is reached, a
+    /// completed transfer makes way for it. A start frame may also take over
+    /// the least recently used ongoing transfer, other frames are rejected
+    /// with [`Error::FrameOrder`]. This bounds memory use on a noisy bus.
+    /// Defaults to [`Reassembler::DEFAULT_MAX_SLOTS`].
+    pub fn with_max_slots(mut self, max_slots: usize) -> Self {
+        self.max_slots = max_slots;
+        self
+    }
+
+    /// Check that successive transfers of each source and data type increment
+    /// the transfer ID by one.
+    ///
+    /// When enabled, a completed transfer whose ID skips ahead of the previous
+    /// one is still returned and [`Reassembler::transfer_id_gap`] reports that
+    /// whole transfers were lost before it. A transfer repeating the ID of the
+    /// previous one is a retransmitted duplicate and is dropped. Disabled by
+    /// default.
+    pub fn with_transfer_id_check(mut self, enabled: bool) -> Self {
+        self.check_transfer_id = enabled;
+        self
+    }
+
+    /// Require every frame of a transfer to have the priority of its start
+    /// frame.
+    ///
+    /// The specification requires all frames of a transfer to share the same
+    /// identifier. When enabled, a frame with a different priority resets the
+    /// transfer and returns [`Error::IdMismatch`]. Disabled by default.
+    pub fn with_strict_priority(mut self, enabled: bool) -> Self {
+        self.strict_priority = enabled;
+        self
+    }
+
+    /// Drop service frames addressed from a node to itself.
+    ///
+    /// These are malformed and usually mean that two nodes share a node ID.
+    /// When enabled, such frames are ignored and `Ok(None)` is returned.
+    /// Disabled by default.
+    pub fn with_drop_self_addressed(mut self, enabled: bool) -> Self {
+        self.drop_self_addressed = enabled;
+        self
+    }
+
+    /// Feed a data frame to the transfer it belongs to.
+    ///
+    /// Returns `Ok(Some(&[u8]))` with the payload when the frame completes a
+    /// transfer.
+    #[must_use = "the payload of a completed transfer is only returned once"]
+    pub fn feed(&mut self, id: Id, data: &[u8]) -> Result<Option<&[u8]>, Error> {
+        Ok(if self.push(id, data)? {
+            Some(self.payload(id))
+        } else {
+            None
+        })
+    }
+
+    /// Feed a frame read from a bxCAN receive FIFO mailbox.
+    ///
+    /// `rir` is the value of the identifier register as described by
+    /// [`Id::from_bxcan_rir`] and `data` the bytes of the data registers
+    /// truncated to the frame length. Returns [`Error::FrameFormat`] for
+    /// standard identifier and remote frames.
+    #[must_use = "the payload of a completed transfer is only returned once"]
+    pub fn feed_bxcan(&mut self, rir: u32, data: &[u8]) -> Result<Option<&[u8]>, Error> {
+        let id = Id::from_bxcan_rir(rir).ok_or(Error::FrameFormat)?;
+        self.feed(id, data)
+    }
+
+    /// Feed a data frame, returning `true` once its transfer is complete.
+    pub(crate) fn push(&mut self, id: Id, data: &[u8]) -> Result<bool, Error> {
+        if self.drop_self_addressed && id.is_self_addressed() {
+            return Ok(false);
+        }
+
+        self.tick = self.tick.wrapping_add(1);
+        let tick = self.tick;
+        let key = Key::new(id);
+
+        if !self.slots.contains_key(&key) && self.slots.len() >= self.max_slots {
+            let start = data.last().is_some_and(|tail| Tail::new(*tail).start());
+            self.evict(start)?;
+        }
+
+        let slot = self.slots.entry(key).or_insert_with(|| Slot::new(tick));
+        slot.last_used = tick;
+
+        if slot.complete {
+            slot.reset();
+        }
+
+        if self.strict_priority && slot.priority.is_some_and(|p| p != id.priority()) {
+            self.slots.remove(&key);
+            return Err(Error::IdMismatch);
+        }
+
+        let result = slot.transfer.feed(None, data);
+        if result.is_ok() {
+            slot.priority.get_or_insert(id.priority());
+        }
+
+        match result {
+            Ok(true) => {
+                let got = slot.transfer.transfer_id();
+                let last = slot.last_transfer_id.replace(got);
+
+                if let Some(last) = last.filter(|_| self.check_transfer_id) {
+                    let expected = (last + 1) % 32;
+
+                    if got == last {
+                        // duplicate of the previous transfer
+                        slot.reset();
+                        return Ok(false);
+                    }
+
+                    if got != expected {
+                        slot.gap = Some(Error::TransferIdGap { expected, got });
+                    }
+                }
+
+                slot.complete = true;
+                Ok(true)
+            }
+            Ok(false) => Ok(false),
+            Err(err) => {
+                self.slots.remove(&key);
+                Err(err)
+            }
+        }
+    }
+
+    /// Feed a received frame, returning its identifier once it completes a
+    /// transfer.
+    ///
+    /// Standard identifier and remote frames are ignored as they can't be part
+    /// of a DroneCAN transfer.
+    pub(crate) fn push_frame(&mut self, frame: &impl Frame) -> Result<Option<Id>, Error> {
+        let id = match frame.id() {
+            embedded_can::Id::Extended(id) if !frame.is_remote_frame() => Id::from(id),
+            _ => return Ok(None),
+        };
+
+        Ok(self.push(id, frame.data())?.then_some(id))
+    }
+
+    /// Make way for a new transfer, preferring completed ones and only taking
+    /// over an ongoing transfer for a `start` frame.
+    fn evict(&mut self, start: bool) -> Result<(), Error> {
+        let oldest = |slots: &BTreeMap<Key, Slot>, complete: bool| {
+            slots
+                .iter()
+                .filter(|(_, slot)| slot.complete == complete)
+                .max_by_key(|(_, slot)| self.tick.wrapping_sub(slot.last_used))
+                .map(|(key, _)| *key)
+        };
+
+        let key = match oldest(&self.slots, true) {
+            Some(key) => key,
+            None if start => oldest(&self.slots, false).ok_or(Error::BufferTooSmall)?,
+            None => return Err(Error::FrameOrder),
+        };
+
+        self.slots.remove(&key);
+        Ok(())
+    }
+
+    /// [`Error::TransferIdGap`] if the last completed transfer `id` belongs
+    /// to skipped ahead of the previous one, see
+    /// [`Reassembler::with_transfer_id_check`].
+    ///
+    /// Returns `None` once a frame of the next transfer arrives.
+    pub fn transfer_id_gap(&self, id: Id) -> Option<Error> {
+        self.slots
+            .get(&Key::new(id))
+            .filter(|slot| slot.complete)
+            .and_then(|slot| slot.gap)
+    }
+
+    /// Data accumulated for the transfer `id` belongs to.
+    pub(crate) fn payload(&self, id: Id) -> &[u8] {
+        match self.slots.get(&Key::new(id)) {
+            Some(slot) => slot.transfer.data(),
+            None => &[],
+        }
+    }
+}
+
+/// Error receiving a transfer from a CAN peripheral.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ReceiveError<E> {
+    /// The peripheral failed to receive a frame.
+    Can(E),
+    /// The received frame could not be added to its transfer.
+    Transfer(Error),
+}
+
+impl<E> From<Error> for ReceiveError<E> {
+    fn from(value: Error) -> Self {
+        Self::Transfer(value)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for ReceiveError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Can(err) => write!(f, "receive failed: {}", err),
+            Self::Transfer(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> core::error::Error for ReceiveError<E> {}
+
+/// Receive frames from a blocking CAN peripheral until a transfer completes.
+///
+/// Standard identifier and remote frames are ignored as they can't be part of a
+/// DroneCAN transfer.
+pub fn receive_transfer<'r, C>(
+    can: &mut C,
+    reassembler: &'r mut Reassembler,
+) -> Result<(Id, &'r [u8]), ReceiveError<C::Error>>
+where
+    C: embedded_can::blocking::Can,
+{
+    let id = loop {
+        let frame = can.receive().map_err(ReceiveError::Can)?;
+
+        if let Some(id) = reassembler.push_frame(&frame)? {
+            break id;
+        }
+    };
+
+    Ok((id, reassembler.payload(id)))
+}
+
+/// Iterator over completed transfers, see [`transfers`].
+#[derive(Debug)]
+pub struct Transfers<I> {
+    frames: I,
+    reassembler: Reassembler,
+}
+
+impl<I, D> Iterator for Transfers<I>
+where
+    I: Iterator<Item = (Id, D)>,
+    D: AsRef<[u8]>,
+{
+    type Item = Result<(Id, Vec<u8>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (id, data) = self.frames.next()?;
+
+            match self.reassembler.feed(id, data.as_ref()) {
+                Ok(Some(payload)) => return Some(Ok((id, payload.to_vec()))),
+                Ok(None) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+/// Turn a stream of frames into a stream of completed transfers.
+///
+/// Errors are yielded as they occur and the affected transfer is reset.
+pub fn transfers<I, D>(frames: I) -> Transfers<I::IntoIter>
+where
+    I: IntoIterator<Item = (Id, D)>,
+    D: AsRef<[u8]>,
+{
+    Transfers {
+        frames: frames.into_iter(),
+        reassembler: Reassembler::new(),
+    }
+}
+
+/// Replay a log of raw identifiers and frame data, such as a `candump`
+/// capture, yielding the completed transfers.
+///
+/// Works like [`transfers`] with `Id`s constructed from the raw values.
+pub fn replay<D>(log: &[(u32, D)]) -> impl Iterator<Item = Result<(Id, Vec<u8>), Error>> + '_
+where
+    D: AsRef<[u8]>,
+{
+    transfers(log.iter().map(|(raw, data)| (Id::new(*raw), data.as_ref())))
+}
+
+/// Extension trait adding [`transfers`] to frame iterators.
+pub trait FramesExt: Iterator + Sized {
+    /// See [`transfers`].
+    fn transfers(self) -> Transfers<Self>;
+}
+
+impl<I, D> FramesExt for I
+where
+    I: Iterator<Item = (Id, D)>,
+    D: AsRef<[u8]>,
+{
+    fn transfers(self) -> Transfers<Self> {
+        transfers(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockCan, MockFrame};
+
+    const ID: u32 = 0x0803F20A;
+    const START: [u8; 8] = [0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D];
+    const END: [u8; 4] = [0x00, 0x7D, 0x33, 0x7D];
+    const PAYLOAD: [u8; 8] = [0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+
+    #[test]
+    fn interleaved_sources() {
+        let mut reassembler = Reassembler::new();
+        let a = Id::new(ID);
+        let b = Id::new(ID + 1); // different source node
+
+        assert_eq!(reassembler.feed(a, &START), Ok(None));
+        assert_eq!(reassembler.feed(b, &START), Ok(None));
+        assert_eq!(reassembler.feed(a, &END), Ok(Some(PAYLOAD.as_ref())));
+        assert_eq!(reassembler.feed(b, &END), Ok(Some(PAYLOAD.as_ref())));
+
+        // completed transfers make way for new ones
+        assert_eq!(reassembler.feed(a, &START), Ok(None));
+        assert_eq!(reassembler.feed(a, &END), Ok(Some(PAYLOAD.as_ref())));
+    }
+
+    #[test]
+    fn interleaved_service_request_response() {
+        let request = Id::Service {
+            priority: 30,
+            service_type: 1,
+            request: true,
+            destination_node: 20,
+            source_node: 10,
+        };
+        let response = Id::Service {
+            priority: 30,
+            service_type: 1,
+            request: false,
+            destination_node: 20,
+            source_node: 10,
+        };
+
+        let mut reassembler = Reassembler::new();
+        assert_eq!(reassembler.feed(request, &START), Ok(None));
+        assert_eq!(reassembler.feed(response, &START), Ok(None));
+        assert_eq!(reassembler.feed(request, &END), Ok(Some(PAYLOAD.as_ref())));
+        assert_eq!(reassembler.feed(response, &END), Ok(Some(PAYLOAD.as_ref())));
+
+        // swapped direction between the same pair of nodes
+        let reply = Id::Service {
+            priority: 30,
+            service_type: 1,
+            request: false,
+            destination_node: 10,
+            source_node: 20,
+        };
+        assert_eq!(reassembler.feed(request, &START), Ok(None));
+        assert_eq!(reassembler.feed(reply, &START), Ok(None));
+        assert_eq!(reassembler.feed(reply, &END), Ok(Some(PAYLOAD.as_ref())));
+        assert_eq!(reassembler.feed(request, &END), Ok(Some(PAYLOAD.as_ref())));
+    }
+
+    /// Same frame sequence as `examples/transfer.rs`.
+    #[test]
+    fn replay_example() {
+        let frames = [
+            (Id::new(0x1234), vec![]),
+            (Id::new(ID), START.to_vec()),
+            (Id::new(ID), END.to_vec()),
+            (Id::new(ID), END.to_vec()),
+            (Id::new(ID), START.to_vec()),
+            (Id::new(ID), END.to_vec()),
+            (Id::new(ID), START.to_vec()),
+            (Id::new(ID), START.to_vec()),
+        ];
+
+        let results: Vec<_> = frames.into_iter().transfers().collect();
+
+        assert_eq!(
+            results,
+            [
+                Err(Error::EmptyFrame),
+                Ok((Id::new(ID), PAYLOAD.to_vec())),
+                Err(Error::FrameOrder),
+                Ok((Id::new(ID), PAYLOAD.to_vec())),
+                Err(Error::FrameOrder),
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_log() {
+        let log: Vec<(u32, Vec<u8>)> = vec![(ID, START.to_vec()), (ID, END.to_vec())];
+
+        let results: Vec<_> = replay(&log).collect();
+        assert_eq!(results, [Ok((Id::new(ID), PAYLOAD.to_vec()))]);
+
+        // borrowed frame data works the same
+        let log: [(u32, &[u8]); 2] = [(ID, &START), (ID, &END)];
+        assert_eq!(replay(&log).count(), 1);
+    }
+
+    #[test]
+    fn drop_self_addressed() {
+        let id = Id::new(0x1F017AFA);
+
+        let mut reassembler = Reassembler::new().with_drop_self_addressed(true);
+        assert_eq!(reassembler.feed(id, &START), Ok(None));
+        assert_eq!(reassembler.feed(id, &END), Ok(None));
+
+        let mut reassembler = Reassembler::new();
+        assert_eq!(reassembler.feed(id, &START), Ok(None));
+        assert_eq!(reassembler.feed(id, &END), Ok(Some(PAYLOAD.as_ref())));
+    }
+
+    #[test]
+    fn feed_bxcan() {
+        let mut reassembler = Reassembler::new();
+        let rir = (ID << 3) | (1 << 2);
+
+        assert_eq!(reassembler.feed_bxcan(rir, &START), Ok(None));
+        assert_eq!(
+            reassembler.feed_bxcan(rir | (1 << 1), &END),
+            Err(Error::FrameFormat)
+        );
+        assert_eq!(
+            reassembler.feed_bxcan(0x123 << 21, &END),
+            Err(Error::FrameFormat)
+        );
+        assert_eq!(
+            reassembler.feed_bxcan(rir, &END),
+            Ok(Some(PAYLOAD.as_ref()))
+        );
+    }
+
+    #[test]
+    fn receive_blocking() {
+        let mut can = MockCan::new([
+            MockFrame::standard(0x123, &[0x00, 0xC0]),
+            MockFrame::remote(ID, 2),
+            MockFrame::extended(ID, &START),
+            MockFrame::extended(ID, &END),
+            MockFrame::extended(ID, &END),
+        ]);
+        let mut reassembler = Reassembler::new();
+
+        let res = receive_transfer(&mut can, &mut reassembler);
+        assert_eq!(res, Ok((Id::new(ID), PAYLOAD.as_ref())));
+
+        let res = receive_transfer(&mut can, &mut reassembler);
+        assert_eq!(res, Err(ReceiveError::Transfer(Error::FrameOrder)));
+
+        // no frames left
+        let res = receive_transfer(&mut can, &mut reassembler);
+        assert_eq!(res, Err(ReceiveError::Can(embedded_can::ErrorKind::Other)));
+    }
+
+    #[test]
+    fn strict_priority() {
+        let start = Id::new(ID);
+        let end = Id::new(ID).with_priority(Id::LOWEST_PRIORITY);
+        let end = end.unwrap_or(start);
+        assert_ne!(start, end);
+
+        let mut reassembler = Reassembler::new().with_strict_priority(true);
+        assert_eq!(reassembler.feed(start, &START), Ok(None));
+        assert_eq!(reassembler.feed(end, &END), Err(Error::IdMismatch));
+
+        // reset after the mismatch
+        assert_eq!(reassembler.feed(end, &START), Ok(None));
+        assert_eq!(reassembler.feed(end, &END), Ok(Some(PAYLOAD.as_ref())));
+
+        let mut reassembler = Reassembler::new();
+        assert_eq!(reassembler.feed(start, &START), Ok(None));
+        assert_eq!(reassembler.feed(end, &END), Ok(Some(PAYLOAD.as_ref())));
+    }
+
+    #[test]
+    fn failed_transfers_drop_slots() {
+        let mut reassembler = Reassembler::new();
+
+        for source in 0..100 {
+            let id = Id::new(ID + source);
+            assert_eq!(reassembler.feed(id, &END), Err(Error::FrameOrder));
+        }
+        assert!(reassembler.slots.is_empty());
+
+        let id = Id::new(ID);
+        assert_eq!(reassembler.feed(id, &START), Ok(None));
+        assert_eq!(reassembler.feed(id, &START), Err(Error::FrameOrder));
+        assert!(reassembler.slots.is_empty());
+    }
+
+    #[test]
+    fn max_slots() {
+        let mut reassembler = Reassembler::new().with_max_slots(2);
+        let a = Id::new(ID);
+        let b = Id::new(ID + 1);
+        let c = Id::new(ID + 2);
+        let d = Id::new(ID + 3);
+
+        assert_eq!(reassembler.feed(a, &START), Ok(None));
+        assert_eq!(reassembler.feed(b, &START), Ok(None));
+        assert_eq!(reassembler.feed(a, &END), Ok(Some(PAYLOAD.as_ref())));
+
+        // the completed transfer of a makes way, b is untouched
+        assert_eq!(reassembler.feed(c, &START), Ok(None));
+        assert_eq!(reassembler.feed(b, &END), Ok(Some(PAYLOAD.as_ref())));
+        assert_eq!(reassembler.feed(c, &END), Ok(Some(PAYLOAD.as_ref())));
+
+        // stray frames never take over an ongoing transfer
+        assert_eq!(reassembler.feed(a, &START), Ok(None));
+        assert_eq!(reassembler.feed(b, &START), Ok(None));
+        assert_eq!(reassembler.feed(d, &END), Err(Error::FrameOrder));
+        assert_eq!(reassembler.slots.len(), 2);
+
+        // start frames take over the least recently used one
+        assert_eq!(reassembler.feed(d, &START), Ok(None));
+        assert_eq!(reassembler.feed(a, &END), Err(Error::FrameOrder));
+        assert_eq!(reassembler.feed(b, &END), Ok(Some(PAYLOAD.as_ref())));
+        assert_eq!(reassembler.feed(d, &END), Ok(Some(PAYLOAD.as_ref())));
+
+        let mut reassembler = Reassembler::new().with_max_slots(0);
+        assert_eq!(reassembler.feed(a, &START), Err(Error::BufferTooSmall));
+    }
+
+    #[test]
+    fn transfer_id_gap() {
+        let id = Id::new(ID);
+
+        let mut reassembler = Reassembler::new().with_transfer_id_check(true);
+        assert_eq!(
+            reassembler.feed(id, &[0x01, 0xC5]),
+            Ok(Some([0x01].as_ref()))
+        );
+        assert_eq!(reassembler.transfer_id_gap(id), None);
+
+        // the transfer after the gap is still received
+        assert_eq!(
+            reassembler.feed(id, &[0x02, 0xC7]),
+            Ok(Some([0x02].as_ref()))
+        );
+        assert_eq!(
+            reassembler.transfer_id_gap(id),
+            Some(Error::TransferIdGap {
+                expected: 6,
+                got: 7
+            })
+        );
+        assert_eq!(
+            reassembler.feed(id, &[0x03, 0xC8]),
+            Ok(Some([0x03].as_ref()))
+        );
+        assert_eq!(reassembler.transfer_id_gap(id), None);
+
+        // duplicates are dropped
+        assert_eq!(reassembler.feed(id, &[0x03, 0xC8]), Ok(None));
+        assert_eq!(reassembler.transfer_id_gap(id), None);
+
+        // wraps around after 31
+        assert_eq!(
+            reassembler.feed(id, &[0x04, 0xDF]),
+            Ok(Some([0x04].as_ref()))
+        );
+        assert_eq!(
+            reassembler.transfer_id_gap(id),
+            Some(Error::TransferIdGap {
+                expected: 9,
+                got: 31
+            })
+        );
+        assert_eq!(
+            reassembler.feed(id, &[0x05, 0xC0]),
+            Ok(Some([0x05].as_ref()))
+        );
+
+        // a new start frame makes way for the next transfer
+        assert_eq!(
+            reassembler.feed(id, &[0x06, 0xC2]),
+            Ok(Some([0x06].as_ref()))
+        );
+        assert!(reassembler.transfer_id_gap(id).is_some());
+        assert_eq!(reassembler.feed(id, &START), Ok(None));
+        assert_eq!(reassembler.transfer_id_gap(id), None);
+
+        // disabled by default
+        let mut reassembler = Reassembler::new();
+        assert_eq!(
+            reassembler.feed(id, &[0x01, 0xC5]),
+            Ok(Some([0x01].as_ref()))
+        );
+        assert_eq!(
+            reassembler.feed(id, &[0x02, 0xC7]),
+            Ok(Some([0x02].as_ref()))
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::{Crc16, Id, TransferId};
 use core::fmt;
 use managed::ManagedSlice;
 
@@ -6,196 +7,1496 @@ use managed::ManagedSlice;
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error {
     DataLength,
+    EmptyFrame,
     BufferTooSmall,
     FrameOrder,
     Crc,
     IdMismatch,
-    Toggle,
+    Toggle { frame_index: usize },
+    TransferIdGap { expected: u8, got: u8 },
+    FrameFormat,
+    FrameTooLong,
+    TransferTooLarge,
+    TooManyFrames,
+}
+
+impl Error {
+    /// Can the transfer be recovered by resetting it and waiting for the next
+    /// start frame?
+    ///
+    /// Malformed frames and frames arriving in an unexpected order are
+    /// recoverable, a frame which doesn't fit the transfer configuration will
+    /// fail the same way again.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            Self::DataLength | Self::BufferTooSmall | Self::FrameTooLong => false,
+            Self::EmptyFrame
+            | Self::FrameOrder
+            | Self::Crc
+            | Self::IdMismatch
+            | Self::Toggle { .. }
+            | Self::TransferIdGap { .. }
+            | Self::FrameFormat
+            | Self::TransferTooLarge
+            | Self::TooManyFrames => true,
+        }
+    }
+
+    /// Opposite of [`Error::is_recoverable`].
+    pub fn is_fatal(&self) -> bool {
+        !self.is_recoverable()
+    }
+
+    /// Category of the error, for mapping into application error types.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::DataLength
+            | Self::EmptyFrame
+            | Self::FrameOrder
+            | Self::IdMismatch
+            | Self::Toggle { .. }
+            | Self::FrameFormat
+            | Self::FrameTooLong => ErrorKind::Protocol,
+            Self::BufferTooSmall | Self::TransferTooLarge | Self::TooManyFrames => {
+                ErrorKind::Resource
+            }
+            Self::Crc | Self::TransferIdGap { .. } => ErrorKind::Integrity,
+        }
+    }
+}
+
+/// Category of an [`Error`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ErrorKind {
+    /// A frame is malformed or doesn't belong in the transfer.
+    Protocol,
+    /// The transfer doesn't fit in the storage or configured limits.
+    Resource,
+    /// Data was corrupted or whole transfers were lost.
+    Integrity,
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::DataLength => write!(f, "data length invalid"),
+            Self::EmptyFrame => write!(f, "frame is empty"),
             Self::BufferTooSmall => write!(f, "buffer is too small"),
             Self::FrameOrder => write!(f, "transfer frame out of order"),
             Self::Crc => write!(f, "CRC check failed"),
             Self::IdMismatch => write!(f, "id mismatch"),
-            Self::Toggle => write!(f, "toggle bit incorrect"),
+            Self::Toggle { frame_index } => {
+                write!(f, "toggle bit incorrect at frame {}", frame_index)
+            }
+            Self::TransferIdGap { expected, got } => {
+                write!(f, "transfer id gap, expected {} got {}", expected, got)
+            }
+            Self::FrameFormat => write!(f, "frame is not an extended data frame"),
+            Self::FrameTooLong => write!(f, "frame exceeds maximum length"),
+            Self::TransferTooLarge => write!(f, "transfer exceeds maximum payload length"),
+            Self::TooManyFrames => write!(f, "transfer exceeds maximum number of frames"),
         }
     }
 }
 
 impl core::error::Error for Error {}
 
-/// Single-frame or multi-frame payload transfer.
+/// Backing storage for a [`Transfer`].
 ///
-/// This implementation doesn't yet verify the checksum.
+/// `N` is the capacity of `heapless::Vec` backed storage and is unused by
+/// the other variants.
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct Transfer<'a> {
-    storage: ManagedSlice<'a, u8>,
+pub enum Storage<'a, const N: usize = 0> {
+    /// Borrowed slice or owned `Vec`.
+    Managed(ManagedSlice<'a, u8>),
+    /// Bounded owned storage that doesn't require an allocator.
+    #[cfg(feature = "heapless")]
+    Heapless(heapless::Vec<u8, N>),
+}
+
+impl<const N: usize> Storage<'_, N> {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Self::Managed(slice) => slice,
+            #[cfg(feature = "heapless")]
+            Self::Heapless(vec) => vec,
+        }
+    }
+
+    /// Write `data` after the first `length` bytes.
+    ///
+    /// Owned storage must already hold exactly `length` bytes.
+    fn write(&mut self, length: usize, data: &[u8]) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "alloc")]
+            Self::Managed(ManagedSlice::Owned(vec)) => {
+                vec.extend_from_slice(data);
+            }
+            Self::Managed(ManagedSlice::Borrowed(slice)) => {
+                if length + data.len() > slice.len() {
+                    return Err(Error::BufferTooSmall);
+                }
+                slice[length..length + data.len()].copy_from_slice(data)
+            }
+            #[cfg(feature = "heapless")]
+            Self::Heapless(vec) => {
+                vec.extend_from_slice(data)
+                    .map_err(|_| Error::BufferTooSmall)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Can `len` more bytes be written after the first `length` bytes?
+    fn fits(&self, length: usize, len: usize) -> bool {
+        match self {
+            #[cfg(feature = "alloc")]
+            Self::Managed(ManagedSlice::Owned(_)) => true,
+            Self::Managed(ManagedSlice::Borrowed(slice)) => length + len <= slice.len(),
+            #[cfg(feature = "heapless")]
+            Self::Heapless(_) => length + len <= N,
+        }
+    }
+
+    /// Deep copy of owned storage, `None` for borrowed slices.
+    fn try_clone(&self) -> Option<Storage<'static, N>> {
+        match self {
+            #[cfg(feature = "alloc")]
+            Self::Managed(ManagedSlice::Owned(vec)) => {
+                Some(Storage::Managed(ManagedSlice::Owned(vec.clone())))
+            }
+            Self::Managed(_) => None,
+            #[cfg(feature = "heapless")]
+            Self::Heapless(vec) => Some(Storage::Heapless(vec.clone())),
+        }
+    }
+
+    /// Reset owned storage to empty.
+    fn clear(&mut self) {
+        match self {
+            #[cfg(feature = "alloc")]
+            Self::Managed(ManagedSlice::Owned(vec)) => vec.clear(),
+            Self::Managed(_) => {}
+            #[cfg(feature = "heapless")]
+            Self::Heapless(vec) => vec.clear(),
+        }
+    }
+}
+
+impl<'a> From<ManagedSlice<'a, u8>> for Storage<'a> {
+    fn from(value: ManagedSlice<'a, u8>) -> Self {
+        Self::Managed(value)
+    }
+}
+
+impl<'a> From<&'a mut [u8]> for Storage<'a> {
+    fn from(value: &'a mut [u8]) -> Self {
+        Self::Managed(value.into())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<alloc::vec::Vec<u8>> for Storage<'_> {
+    fn from(value: alloc::vec::Vec<u8>) -> Self {
+        Self::Managed(value.into())
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> From<heapless::Vec<u8, N>> for Storage<'_, N> {
+    fn from(value: heapless::Vec<u8, N>) -> Self {
+        Self::Heapless(value)
+    }
+}
+
+/// Receiver of streamed inner data, see [`Transfer::with_sink`].
+type Sink<'a> = &'a mut dyn FnMut(&[u8]);
+
+/// Single-frame or multi-frame payload transfer.
+///
+/// `N` is only relevant when using `heapless::Vec` storage.
+///
+/// The CRC of multi-frame transfers is only verified when the data type
+/// signature is set with [`Transfer::with_data_type_signature`].
+pub struct Transfer<'a, const N: usize = 0> {
+    storage: Storage<'a, N>,
     length: usize,
+    frames: usize,
+    complete: bool,
     transfer_id: u8,
     toggle: bool,
+    crc: Option<u16>,
+    id: Option<Id>,
+    max_frame_len: usize,
+    max_payload_len: usize,
+    max_frames: usize,
+    signature: Option<u64>,
+    single_frame_crc: bool,
+    lenient_toggle: bool,
+    include_crc: bool,
+    auto_restart: bool,
+    running_crc: Option<Crc16>,
+    crc_ok: Option<bool>,
+    streamed: usize,
+    #[cfg(feature = "alloc")]
+    boundaries: Option<alloc::vec::Vec<usize>>,
+    observer: Option<&'a mut dyn FnMut(FrameEvent)>,
+    sink: Option<Sink<'a>>,
 }
 
 impl<'a> Transfer<'a> {
+    /// Create a new empty transfer using only the first `capacity` bytes of
+    /// `storage`.
+    ///
+    /// Returns `None` if `capacity` is larger than `storage`.
+    pub fn with_capacity(storage: &'a mut [u8], capacity: usize) -> Option<Self> {
+        storage.get_mut(..capacity).map(Self::new)
+    }
+
+    /// Configure a transfer without chaining `with_` methods on it.
+    pub fn builder() -> TransferBuilder {
+        TransferBuilder::default()
+    }
+}
+
+impl<'a, const N: usize> Transfer<'a, N> {
+    /// Default limit of [`Transfer::with_max_payload_len`] for owned `Vec`
+    /// storage, comfortably above the largest standard data type.
+    pub const DEFAULT_MAX_PAYLOAD_LEN: usize = 1024;
+
     /// Create a new empty transfer.
     ///
     /// Every element in `storage` will be reset.
     pub fn new<S>(storage: S) -> Self
     where
-        S: Into<ManagedSlice<'a, u8>>,
+        S: Into<Storage<'a, N>>,
     {
         let mut storage = storage.into();
+        storage.clear();
 
-        if let ManagedSlice::Owned(vec) = &mut storage {
-            vec.clear();
-        }
+        // other storage is already bounded by its capacity
+        let max_payload_len = if storage.fits(0, usize::MAX) {
+            Self::DEFAULT_MAX_PAYLOAD_LEN
+        } else {
+            usize::MAX
+        };
 
         Self {
             storage,
             length: 0,
+            frames: 0,
+            complete: false,
             transfer_id: 0,
             toggle: false,
+            crc: None,
+            id: None,
+            max_frame_len: 8,
+            max_payload_len,
+            max_frames: usize::MAX,
+            signature: None,
+            single_frame_crc: false,
+            lenient_toggle: false,
+            include_crc: false,
+            auto_restart: false,
+            running_crc: None,
+            crc_ok: None,
+            streamed: 0,
+            #[cfg(feature = "alloc")]
+            boundaries: None,
+            observer: None,
+            sink: None,
         }
     }
 
+    /// Set the maximum accepted frame length including the tail byte.
+    ///
+    /// Defaults to 8 bytes for classic CAN.
+    pub fn with_max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// Set the maximum accepted payload length, including streamed data.
+    ///
+    /// Frames which would grow the payload beyond it are rejected with
+    /// [`Error::TransferTooLarge`] so a start frame which is never ended can't
+    /// exhaust memory. Defaults to [`Transfer::DEFAULT_MAX_PAYLOAD_LEN`] for
+    /// owned `Vec` storage and is unlimited for other storage, which can't grow
+    /// beyond its capacity.
+    pub fn with_max_payload_len(mut self, max_payload_len: usize) -> Self {
+        self.max_payload_len = max_payload_len;
+        self
+    }
+
+    /// Set the maximum number of frames in a transfer.
+    ///
+    /// A frame beyond it is rejected with [`Error::TooManyFrames`] and the
+    /// transfer is abandoned, so the following frames must start a new one.
+    /// Unlimited by default, bounding the number of frames as well as the
+    /// payload length guards against streams of tiny frames which never end.
+    pub fn with_max_frames(mut self, max_frames: usize) -> Self {
+        self.max_frames = max_frames;
+        self
+    }
+
+    /// Set the toggle bit expected on the first continuation frame.
+    ///
+    /// This only matters when joining a transfer whose start frame was
+    /// consumed elsewhere, a start frame always sets the toggle bit for the
+    /// rest of the transfer. Defaults to `true`, the toggle bit of the frame
+    /// following a start frame.
+    pub fn with_initial_toggle(mut self, toggle: bool) -> Self {
+        // the stored toggle is that of the previous frame
+        self.toggle = !toggle;
+        self
+    }
+
+    /// Record where each accepted frame ends so they can be inspected with
+    /// [`Transfer::frames`].
+    ///
+    /// This is intended for diagnosing failed transfers and allocates for every
+    /// frame. Disabled by default.
+    #[cfg(feature = "alloc")]
+    pub fn with_frame_tracking(mut self, enabled: bool) -> Self {
+        self.boundaries = enabled.then(alloc::vec::Vec::new);
+        self
+    }
+
+    /// Accept single-frame transfers with the toggle bit set.
+    ///
+    /// The specification requires the toggle bit of a single frame to be
+    /// cleared and [`Error::Toggle`] is returned otherwise. Disabled by
+    /// default.
+    pub fn with_lenient_toggle(mut self, enabled: bool) -> Self {
+        self.lenient_toggle = enabled;
+        self
+    }
+
+    /// Call `observer` for every accepted frame.
+    ///
+    /// Intended for monitoring, it is not called for rejected frames.
+    pub fn with_observer(mut self, observer: &'a mut dyn FnMut(FrameEvent)) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Pass the inner data of every accepted frame to `sink` instead of
+    /// storing it.
+    ///
+    /// The storage is left untouched so transfers larger than the available
+    /// memory can be processed, an empty slice can be used. Set the data type
+    /// signature as well to have the CRC verified when the end frame arrives.
+    pub fn with_sink(mut self, sink: &'a mut dyn FnMut(&[u8])) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Set the data type signature used to compute the transfer CRC.
+    ///
+    /// The CRC of multi-frame transfers is then updated as frames arrive and
+    /// [`Error::Crc`] is returned for an end frame which doesn't match it.
+    pub fn with_data_type_signature(mut self, signature: u64) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
+    /// Accept single-frame transfers which carry a CRC like a start frame.
+    ///
+    /// This is not part of the specification but some senders include the
+    /// CRC even when the payload fits in a single frame. When enabled, the
+    /// first two bytes of a single frame are treated as a CRC and stripped
+    /// if they match the CRC of the rest of the frame. Requires the data type
+    /// signature to be set with [`Transfer::with_data_type_signature`].
+    ///
+    /// A genuine payload whose first two bytes happen to match its own CRC
+    /// will be truncated, so only enable this for senders known to need it.
+    pub fn with_single_frame_crc(mut self, enabled: bool) -> Self {
+        self.single_frame_crc = enabled;
+        self
+    }
+
+    /// Keep the CRC of multi-frame transfers at the start of the payload.
+    ///
+    /// By default the two CRC bytes are stripped from the start frame. When
+    /// enabled they are retained so the raw transfer bytes can be forwarded
+    /// or verified again later. [`Transfer::received_crc`] is set either way.
+    pub fn with_include_crc(mut self, enabled: bool) -> Self {
+        self.include_crc = enabled;
+        self
+    }
+
+    /// Abandon the partial transfer when a new start frame arrives.
+    ///
+    /// By default a start frame received while a transfer is in progress is
+    /// rejected with [`Error::FrameOrder`]. When enabled the data accumulated
+    /// so far is discarded and a new transfer begins, so a sender retrying a
+    /// transmission isn't lost. A start frame which is itself rejected leaves
+    /// the partial transfer untouched.
+    pub fn with_auto_restart(mut self, enabled: bool) -> Self {
+        self.auto_restart = enabled;
+        self
+    }
+
     /// Feed data frames to the ongoing transfer.
     ///
     /// If the frame is accepted `Ok(None)` will be returned or
     /// `Ok(Some(&[u8]))` with the inner data if the last data frame was marked
     /// as the end of the transfer.
     ///
+    /// A frame of only the tail byte is accepted as an empty single-frame
+    /// transfer and rejected with [`Error::FrameOrder`] otherwise.
+    ///
     /// If an [`Error`] is returned, the transfer should probably be abandoned.
+    #[must_use = "the payload of a completed transfer is only returned once"]
     pub fn add_frame(&mut self, data: &[u8]) -> Result<Option<&[u8]>, Error> {
-        if data.len() > 8 {
-            return Err(Error::DataLength);
+        Ok(if self.feed(None, data)? {
+            Some(self.data())
+        } else {
+            None
+        })
+    }
+
+    /// Feed a data frame, returning `true` once the transfer is complete.
+    ///
+    /// The transfer holds all of the reassembly state and the frame is the
+    /// only input, which makes this the entry point for fuzzing with arbitrary
+    /// frames. It never panics, whatever the frame contains.
+    pub fn ingest(&mut self, data: &[u8]) -> Result<bool, Error> {
+        self.feed(None, data)
+    }
+
+    /// Feed several data frames to the ongoing transfer.
+    ///
+    /// Stops at the first frame which completes the transfer or returns an
+    /// error, remaining frames are ignored.
+    #[must_use = "the payload of a completed transfer is only returned once"]
+    pub fn add_frames(&mut self, frames: &[&[u8]]) -> Result<Option<&[u8]>, Error> {
+        for frame in frames {
+            if self.feed(None, frame)? {
+                return Ok(Some(self.data()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Feed data frames to the ongoing transfer, verifying their identifier.
+    ///
+    /// Same as [`Transfer::add_frame`] but the identifier of the start frame is
+    /// recorded and [`Error::IdMismatch`] is returned if a subsequent frame
+    /// belongs to a different source or data type. Priority is not compared.
+    #[must_use = "the payload of a completed transfer is only returned once"]
+    pub fn add_frame_with_id(&mut self, id: Id, data: &[u8]) -> Result<Option<&[u8]>, Error> {
+        Ok(if self.feed(Some(id), data)? {
+            Some(self.data())
+        } else {
+            None
+        })
+    }
+
+    /// Feed a CAN frame to the ongoing transfer, verifying its identifier.
+    ///
+    /// Same as [`Transfer::add_frame_with_id`] taking the identifier and data
+    /// from `frame`. Returns [`Error::FrameFormat`] for standard identifier and
+    /// remote frames which can't be part of a DroneCAN transfer.
+    #[must_use = "the payload of a completed transfer is only returned once"]
+    pub fn add_can_frame<F>(&mut self, frame: &F) -> Result<Option<&[u8]>, Error>
+    where
+        F: embedded_can::Frame,
+    {
+        let id = match frame.id() {
+            embedded_can::Id::Extended(id) if !frame.is_remote_frame() => Id::from(id),
+            _ => return Err(Error::FrameFormat),
+        };
+
+        self.add_frame_with_id(id, frame.data())
+    }
+
+    /// Feed a data frame, returning `true` once the transfer is complete.
+    pub(crate) fn feed(&mut self, id: Option<Id>, data: &[u8]) -> Result<bool, Error> {
+        if data.len() > self.max_frame_len {
+            return Err(Error::FrameTooLong);
         }
 
         let tail = match data.last() {
             Some(d) => Tail(*d),
-            None => return Err(Error::DataLength),
+            None => return Err(Error::EmptyFrame),
         };
 
-        if tail.start() && self.length != 0 {
+        // a tail byte alone is only valid as an empty single-frame transfer
+        if data.len() == 1 && !(tail.start() && tail.end()) {
+            return Err(Error::FrameOrder);
+        }
+
+        // multi-frame start frames must fit the crc and tail byte
+        if tail.start() && !tail.end() && data.len() < 3 {
+            return Err(Error::DataLength);
+        }
+
+        if tail.start() && self.frames != 0 && !self.auto_restart {
             // this is not the first transfer
             return Err(Error::FrameOrder);
         }
 
+        if tail.start() && tail.end() && tail.toggle() && !self.lenient_toggle {
+            return Err(Error::Toggle { frame_index: 0 });
+        }
+
+        let has_crc = tail.start() && (!tail.end() || self.has_single_frame_crc(data));
+        let inner_data = if has_crc && !self.include_crc {
+            &data[2..data.len() - 1]
+        } else {
+            // single frame transfers don't start with crc
+            &data[..data.len() - 1]
+        };
+
+        // a start frame is checked as the first frame of a new transfer
+        let received = if tail.start() { 0 } else { self.received() };
+
+        // checked before a start frame changes any state
+        if received + inner_data.len() > self.max_payload_len {
+            return Err(Error::TransferTooLarge);
+        }
+
+        if tail.start() && self.sink.is_none() && !self.storage.fits(0, inner_data.len()) {
+            return Err(Error::BufferTooSmall);
+        }
+
+        if tail.start() && self.max_frames == 0 {
+            return Err(Error::TooManyFrames);
+        }
+
         if tail.start() {
+            // only abandon the previous transfer once the new one is accepted
+            if self.frames != 0 {
+                self.restart();
+            }
+
             self.transfer_id = tail.transfer_id();
             self.toggle = tail.toggle();
+            self.crc = None;
+            self.crc_ok = None;
+            self.id = id;
         } else {
             // we cannot start with an end frame
-            if self.length == 0 && tail.end() {
+            if self.frames == 0 && tail.end() {
                 return Err(Error::FrameOrder);
             }
 
+            if let (Some(expected), Some(id)) = (self.id, id) {
+                if !expected.matches_ignoring_priority(&id) {
+                    return Err(Error::IdMismatch);
+                }
+            }
+
             if self.transfer_id != tail.transfer_id() {
                 return Err(Error::IdMismatch);
             }
 
             if self.toggle == tail.toggle() {
-                return Err(Error::Toggle);
-            } else {
-                self.toggle = tail.toggle();
+                return Err(Error::Toggle {
+                    frame_index: self.frames,
+                });
             }
-        }
 
-        let inner_data = if tail.start() && !tail.end() {
-            &data[2..data.len() - 1]
-        } else {
-            // single frame transfers don't start with crc
-            &data[..data.len() - 1]
-        };
+            if self.frames >= self.max_frames {
+                // a transfer which never ends is abandoned
+                self.restart();
+                self.crc = None;
+                self.crc_ok = None;
+                self.running_crc = None;
+                return Err(Error::TooManyFrames);
+            }
+        }
 
-        match &mut self.storage {
-            #[cfg(feature = "alloc")]
-            ManagedSlice::Owned(vec) => {
-                vec.extend_from_slice(inner_data);
+        // a new transfer starts without the crc of the previous one
+        let mut running_crc = if tail.start() { None } else { self.running_crc };
+        if has_crc {
+            self.crc = Some(u16::from_le_bytes([data[0], data[1]]));
+            if !tail.end() {
+                running_crc = self.signature.map(Crc16::with_signature);
             }
-            ManagedSlice::Borrowed(slice) => {
-                if self.length + inner_data.len() > slice.len() {
-                    return Err(Error::BufferTooSmall);
+        }
+
+        if let Some(crc) = &mut running_crc {
+            let skip = if tail.start() && self.include_crc {
+                2
+            } else {
+                0
+            };
+            crc.add(inner_data.get(skip..).unwrap_or_default());
+
+            if tail.end() {
+                let ok = Some(crc.value()) == self.crc;
+                self.crc_ok = Some(ok);
+
+                if !ok {
+                    return Err(Error::Crc);
                 }
-                slice[self.length..self.length + inner_data.len()].copy_from_slice(inner_data)
             }
         }
 
-        self.length += inner_data.len();
+        if let Some(sink) = &mut self.sink {
+            sink(inner_data);
+            self.streamed += inner_data.len();
+        } else {
+            self.storage.write(self.length, inner_data)?;
+            self.length += inner_data.len();
+        }
+
+        // only advance once the frame is stored so it can be retried
+        self.toggle = tail.toggle();
+        self.running_crc = running_crc;
+        #[cfg(feature = "alloc")]
+        if let Some(boundaries) = &mut self.boundaries {
+            boundaries.push(self.length);
+        }
+        self.frames += 1;
+        self.complete = tail.end();
+
+        if let Some(observer) = &mut self.observer {
+            observer(FrameEvent {
+                position: tail.position(),
+                transfer_id: tail.transfer_id(),
+                toggle: tail.toggle(),
+                len: inner_data.len(),
+            });
+        }
+
+        Ok(self.complete)
+    }
+
+    /// Replace the storage, keeping the data accumulated so far.
+    ///
+    /// Allows a transfer which failed with [`Error::BufferTooSmall`] to continue
+    /// with a larger buffer, the rejected frame can then be fed again. Returns
+    /// [`Error::BufferTooSmall`] and keeps the current storage if the
+    /// accumulated data doesn't fit in `storage`.
+    pub fn set_storage<S>(&mut self, storage: S) -> Result<(), Error>
+    where
+        S: Into<Storage<'a, N>>,
+    {
+        let mut storage = storage.into();
+        storage.clear();
+        storage.write(0, self.data())?;
+
+        self.storage = storage;
+        Ok(())
+    }
+
+    /// Force completion of the transfer, returning the data accumulated so far.
+    ///
+    /// This is an escape hatch for best-effort decoding of streams where the
+    /// end frame was lost. The payload may be truncated and is not verified in
+    /// any way, so it should only be used where partial data is acceptable.
+    ///
+    /// Returns [`Error::FrameOrder`] if no frame has been accepted.
+    #[must_use = "the accumulated data is returned"]
+    pub fn finish(&mut self) -> Result<&[u8], Error> {
+        if self.frames == 0 {
+            return Err(Error::FrameOrder);
+        }
+
+        Ok(self.data())
+    }
 
-        Ok(if tail.end() {
-            // todo: crc check
-            Some(&self.storage[..self.length])
+    /// Payload of the transfer if it has completed.
+    #[must_use]
+    pub fn payload(&self) -> Option<&[u8]> {
+        if self.complete {
+            Some(self.data())
         } else {
             None
-        })
+        }
     }
-}
 
-/// Newtype for interpreting the tail byte.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Tail(u8);
+    /// Inner data of each frame accepted so far.
+    ///
+    /// Yields nothing unless enabled with [`Transfer::with_frame_tracking`].
+    #[cfg(feature = "alloc")]
+    pub fn frames(&self) -> impl Iterator<Item = &[u8]> {
+        let data = self.data();
+        let ends = self.boundaries.as_deref().unwrap_or(&[]);
+        let starts = core::iter::once(0).chain(ends.iter().copied());
 
-impl Tail {
-    /// Start of transfer.
-    fn start(&self) -> bool {
-        (self.0 & (1 << 7)) != 0
+        starts
+            .zip(ends.iter().copied())
+            .map(move |(start, end)| &data[start..end])
     }
 
-    /// End of transfer.
-    fn end(&self) -> bool {
-        (self.0 & (1 << 6)) != 0
+    /// Has the end frame of the transfer been accepted?
+    pub fn completed(&self) -> bool {
+        self.complete
     }
 
-    /// Toggle bit (inverts every payload).
-    fn toggle(&self) -> bool {
-        (self.0 & (1 << 5)) != 0
+    /// Transfer CRC stripped from the start frame.
+    ///
+    /// `None` until a start frame carrying a CRC is accepted, single-frame
+    /// transfers don't have one.
+    pub fn received_crc(&self) -> Option<u16> {
+        self.crc
     }
 
-    /// Transfer identifier.
-    fn transfer_id(&self) -> u8 {
-        self.0 & 0x1F
+    /// Copy an in-progress transfer so both copies can be fed independently.
+    ///
+    /// Owned `Vec` and `heapless::Vec` storage is deep-copied. Borrowed
+    /// slices can't be shared mutably so `None` is returned for them, as it
+    /// is for transfers streaming to a sink. The observer is not carried over
+    /// to the copy.
+    pub fn try_clone(&self) -> Option<Transfer<'static, N>> {
+        if self.sink.is_some() {
+            return None;
+        }
+
+        Some(Transfer {
+            storage: self.storage.try_clone()?,
+            length: self.length,
+            frames: self.frames,
+            complete: self.complete,
+            transfer_id: self.transfer_id,
+            toggle: self.toggle,
+            crc: self.crc,
+            id: self.id,
+            max_frame_len: self.max_frame_len,
+            max_payload_len: self.max_payload_len,
+            max_frames: self.max_frames,
+            signature: self.signature,
+            single_frame_crc: self.single_frame_crc,
+            lenient_toggle: self.lenient_toggle,
+            include_crc: self.include_crc,
+            auto_restart: self.auto_restart,
+            running_crc: self.running_crc,
+            crc_ok: self.crc_ok,
+            streamed: self.streamed,
+            #[cfg(feature = "alloc")]
+            boundaries: self.boundaries.clone(),
+            observer: None,
+            sink: None,
+        })
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Did the CRC of the multi-frame transfer match?
+    ///
+    /// `None` until the end frame has been processed or if the data type
+    /// signature isn't set, the result is kept when the end frame is rejected
+    /// with [`Error::Crc`].
+    pub fn crc_ok(&self) -> Option<bool> {
+        self.crc_ok
+    }
 
-    #[test]
-    fn tail_byte() {
-        let tail = Tail(0xFF);
-        assert!(tail.start());
-        assert!(tail.end());
-        assert!(tail.toggle());
-        assert!(tail.transfer_id() == 31);
+    /// Does a single frame start with a CRC of its payload?
+    fn has_single_frame_crc(&self, data: &[u8]) -> bool {
+        match self.signature {
+            Some(signature) if self.single_frame_crc && data.len() >= 3 => {
+                let mut crc = Crc16::with_signature(signature);
+                crc.add(&data[2..data.len() - 1]);
+                crc.value() == u16::from_le_bytes([data[0], data[1]])
+            }
+            _ => false,
+        }
+    }
 
-        let tail = Tail(0x7C);
-        assert!(!tail.start());
-        assert!(tail.end());
-        assert!(tail.toggle());
-        assert!(tail.transfer_id() == 28)
+    /// Discard the data accumulated so far, keeping the configuration.
+    fn restart(&mut self) {
+        self.storage.clear();
+        self.length = 0;
+        self.streamed = 0;
+        self.frames = 0;
+        self.complete = false;
+        #[cfg(feature = "alloc")]
+        if let Some(boundaries) = &mut self.boundaries {
+            boundaries.clear();
+        }
     }
 
-    #[test]
+    /// Number of inner data bytes accepted so far, stored or streamed.
+    fn received(&self) -> usize {
+        self.length + self.streamed
+    }
+
+    /// Data accumulated so far.
+    pub(crate) fn data(&self) -> &[u8] {
+        &self.storage.as_slice()[..self.length]
+    }
+
+    /// Transfer ID of the current transfer.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn transfer_id(&self) -> u8 {
+        self.transfer_id
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Transfer<'_> {
+    /// Split a payload into the classic CAN frames of a transfer.
+    ///
+    /// Multi-frame transfers start with the transfer CRC seeded with the data
+    /// type `signature`. This is the inverse of feeding the frames to a
+    /// transfer and is mainly useful for testing codecs.
+    pub fn from_payload(
+        payload: &[u8],
+        transfer_id: u8,
+        signature: u64,
+    ) -> alloc::vec::Vec<alloc::vec::Vec<u8>> {
+        let transfer_id = transfer_id & 0x1F;
+
+        if payload.len() < 8 {
+            let mut frame = payload.to_vec();
+            frame.push(0xC0 | transfer_id);
+            return alloc::vec![frame];
+        }
+
+        let mut crc = Crc16::with_signature(signature);
+        crc.add(payload);
+
+        let mut data = crc.value().to_le_bytes().to_vec();
+        data.extend_from_slice(payload);
+
+        let count = data.len().div_ceil(7);
+        data.chunks(7)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut tail = transfer_id;
+                if i == 0 {
+                    tail |= 1 << 7;
+                }
+                if i == count - 1 {
+                    tail |= 1 << 6;
+                }
+                if i % 2 == 1 {
+                    tail |= 1 << 5;
+                }
+
+                let mut frame = chunk.to_vec();
+                frame.push(tail);
+                frame
+            })
+            .collect()
+    }
+
+    /// Consume the transfer, returning the data accumulated so far.
+    ///
+    /// Owned `Vec` storage is handed back without copying, borrowed storage
+    /// is copied into a new `Vec`.
+    pub fn into_payload(self) -> alloc::vec::Vec<u8> {
+        match self.storage {
+            Storage::Managed(ManagedSlice::Owned(vec)) => vec,
+            Storage::Managed(ManagedSlice::Borrowed(slice)) => slice[..self.length].to_vec(),
+            #[cfg(feature = "heapless")]
+            Storage::Heapless(vec) => vec.to_vec(),
+        }
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Transfer<'_> {
+    /// Consume the transfer, returning the data accumulated so far as
+    /// cheaply cloneable [`bytes::Bytes`].
+    ///
+    /// Owned `Vec` storage is handed over without copying, see
+    /// [`Transfer::into_payload`].
+    pub fn into_bytes(self) -> bytes::Bytes {
+        self.into_payload().into()
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> Transfer<'_, N> {
+    /// Consume the transfer, returning the data accumulated so far.
+    ///
+    /// `heapless::Vec` storage is handed back without copying, other storage
+    /// is copied and returns [`Error::BufferTooSmall`] if the data doesn't fit
+    /// in `N` bytes.
+    pub fn into_heapless_payload(self) -> Result<heapless::Vec<u8, N>, Error> {
+        match self.storage {
+            Storage::Heapless(vec) => Ok(vec),
+            Storage::Managed(slice) => {
+                heapless::Vec::from_slice(&slice[..self.length]).map_err(|_| Error::BufferTooSmall)
+            }
+        }
+    }
+}
+
+/// Decode a single-frame transfer without keeping any state.
+///
+/// Returns the payload without the tail byte, [`Error::FrameOrder`] if the
+/// frame is not both the start and end of a transfer or [`Error::Toggle`] if
+/// its toggle bit is set.
+#[must_use = "the payload is returned"]
+pub fn decode_single(data: &[u8]) -> Result<&[u8], Error> {
+    if data.len() > 8 {
+        return Err(Error::FrameTooLong);
+    }
+
+    let (tail, payload) = match data.split_last() {
+        Some((tail, payload)) => (Tail(*tail), payload),
+        None => return Err(Error::EmptyFrame),
+    };
+
+    if !(tail.start() && tail.end()) {
+        return Err(Error::FrameOrder);
+    }
+
+    if tail.toggle() {
+        return Err(Error::Toggle { frame_index: 0 });
+    }
+
+    Ok(payload)
+}
+
+/// Number of classic CAN frames needed to send a payload of `payload_len`
+/// bytes.
+pub fn frame_count(payload_len: usize) -> usize {
+    frame_count_with_max(payload_len, 8)
+}
+
+/// Number of frames of at most `max_frame_len` bytes needed to send a payload
+/// of `payload_len` bytes.
+///
+/// Every frame carries a tail byte and multi-frame transfers are prefixed with
+/// a 2-byte CRC.
+pub fn frame_count_with_max(payload_len: usize, max_frame_len: usize) -> usize {
+    let per_frame = max_frame_len.saturating_sub(1).max(1);
+
+    if payload_len <= per_frame {
+        1
+    } else {
+        (payload_len + 2).div_ceil(per_frame)
+    }
+}
+
+/// Configuration of a [`Transfer`], see [`Transfer::builder`].
+///
+/// Every option defaults to the same value as [`Transfer::new`] and has the
+/// same meaning as the `with_` method of the same name on [`Transfer`]. The
+/// observer and sink are borrowed so they are set on the built transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TransferBuilder {
+    max_frame_len: usize,
+    max_payload_len: Option<usize>,
+    max_frames: usize,
+    initial_toggle: Option<bool>,
+    #[cfg(feature = "alloc")]
+    frame_tracking: bool,
+    lenient_toggle: bool,
+    signature: Option<u64>,
+    single_frame_crc: bool,
+    include_crc: bool,
+    auto_restart: bool,
+}
+
+impl Default for TransferBuilder {
+    fn default() -> Self {
+        Self {
+            max_frame_len: 8,
+            max_payload_len: None,
+            max_frames: usize::MAX,
+            initial_toggle: None,
+            #[cfg(feature = "alloc")]
+            frame_tracking: false,
+            lenient_toggle: false,
+            signature: None,
+            single_frame_crc: false,
+            include_crc: false,
+            auto_restart: false,
+        }
+    }
+}
+
+impl TransferBuilder {
+    /// See [`Transfer::with_max_frame_len`].
+    pub fn max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// See [`Transfer::with_max_payload_len`].
+    pub fn max_payload_len(mut self, max_payload_len: usize) -> Self {
+        self.max_payload_len = Some(max_payload_len);
+        self
+    }
+
+    /// See [`Transfer::with_max_frames`].
+    pub fn max_frames(mut self, max_frames: usize) -> Self {
+        self.max_frames = max_frames;
+        self
+    }
+
+    /// See [`Transfer::with_initial_toggle`].
+    pub fn initial_toggle(mut self, toggle: bool) -> Self {
+        self.initial_toggle = Some(toggle);
+        self
+    }
+
+    /// See [`Transfer::with_frame_tracking`].
+    #[cfg(feature = "alloc")]
+    pub fn frame_tracking(mut self, enabled: bool) -> Self {
+        self.frame_tracking = enabled;
+        self
+    }
+
+    /// See [`Transfer::with_lenient_toggle`].
+    pub fn lenient_toggle(mut self, enabled: bool) -> Self {
+        self.lenient_toggle = enabled;
+        self
+    }
+
+    /// See [`Transfer::with_data_type_signature`].
+    pub fn data_type_signature(mut self, signature: u64) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
+    /// See [`Transfer::with_single_frame_crc`].
+    pub fn single_frame_crc(mut self, enabled: bool) -> Self {
+        self.single_frame_crc = enabled;
+        self
+    }
+
+    /// See [`Transfer::with_include_crc`].
+    pub fn include_crc(mut self, enabled: bool) -> Self {
+        self.include_crc = enabled;
+        self
+    }
+
+    /// See [`Transfer::with_auto_restart`].
+    pub fn auto_restart(mut self, enabled: bool) -> Self {
+        self.auto_restart = enabled;
+        self
+    }
+
+    /// Create a new empty transfer with this configuration.
+    ///
+    /// Every element in `storage` will be reset.
+    pub fn build<'a, S, const N: usize>(self, storage: S) -> Transfer<'a, N>
+    where
+        S: Into<Storage<'a, N>>,
+    {
+        let mut transfer = Transfer::new(storage)
+            .with_max_frame_len(self.max_frame_len)
+            .with_max_frames(self.max_frames)
+            .with_lenient_toggle(self.lenient_toggle)
+            .with_single_frame_crc(self.single_frame_crc)
+            .with_include_crc(self.include_crc)
+            .with_auto_restart(self.auto_restart);
+
+        #[cfg(feature = "alloc")]
+        {
+            transfer = transfer.with_frame_tracking(self.frame_tracking);
+        }
+
+        if let Some(max_payload_len) = self.max_payload_len {
+            transfer = transfer.with_max_payload_len(max_payload_len);
+        }
+
+        if let Some(toggle) = self.initial_toggle {
+            transfer = transfer.with_initial_toggle(toggle);
+        }
+
+        if let Some(signature) = self.signature {
+            transfer = transfer.with_data_type_signature(signature);
+        }
+
+        transfer
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Default for Transfer<'_> {
+    /// Empty transfer with owned `Vec` storage.
+    fn default() -> Self {
+        Self::new(alloc::vec::Vec::new())
+    }
+}
+
+/// Number of payload bytes shown when formatting a [`Transfer`].
+const PREVIEW_LEN: usize = 8;
+
+impl<const N: usize> fmt::Debug for Transfer<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = self.data();
+        let preview = &data[..data.len().min(PREVIEW_LEN)];
+
+        f.debug_struct("Transfer")
+            .field("len", &self.length)
+            .field("transfer_id", &self.transfer_id)
+            .field("toggle", &self.toggle)
+            .field("data", &format_args!("{:02x?}", preview))
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const N: usize> defmt::Format for Transfer<'_, N> {
+    fn format(&self, f: defmt::Formatter) {
+        let data = self.data();
+        let preview = &data[..data.len().min(PREVIEW_LEN)];
+
+        defmt::write!(
+            f,
+            "Transfer {{ len: {=usize}, transfer_id: {=u8}, toggle: {=bool}, data: {=[u8]:02x}, .. }}",
+            self.length,
+            self.transfer_id,
+            self.toggle,
+            preview,
+        )
+    }
+}
+
+/// Position of a frame within its transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FramePosition {
+    /// Both the start and end of a single-frame transfer.
+    Single,
+    Start,
+    Middle,
+    End,
+}
+
+/// A frame accepted by a [`Transfer`], see [`Transfer::with_observer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FrameEvent {
+    /// Position of the frame within the transfer.
+    pub position: FramePosition,
+    /// Transfer identifier from the tail byte.
+    pub transfer_id: u8,
+    /// Toggle bit from the tail byte.
+    pub toggle: bool,
+    /// Length of the inner data without CRC or tail byte.
+    pub len: usize,
+}
+
+/// Flags of a tail byte, combined with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TailFlags(u8);
+
+impl TailFlags {
+    /// First frame of a transfer.
+    pub const START: TailFlags = TailFlags(1 << 7);
+    /// Last frame of a transfer.
+    pub const END: TailFlags = TailFlags(1 << 6);
+    /// Toggle bit, alternating between the frames of a transfer.
+    pub const TOGGLE: TailFlags = TailFlags(1 << 5);
+
+    /// No flags set, a middle frame with the toggle bit clear.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Are all flags in `other` set?
+    pub const fn contains(self, other: TailFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for TailFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for TailFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Newtype for interpreting the tail byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Tail(u8);
+
+impl Tail {
+    /// Interpret a raw tail byte.
+    pub const fn new(value: u8) -> Self {
+        Self(value)
+    }
+
+    /// Build a tail byte from `flags` and `transfer_id` masked to five bits.
+    pub const fn from_flags(flags: TailFlags, transfer_id: u8) -> Self {
+        Self(flags.0 | (transfer_id & 0x1F))
+    }
+
+    /// Build a tail byte from `flags` and a validated transfer ID.
+    pub const fn from_transfer_id(flags: TailFlags, transfer_id: TransferId) -> Self {
+        Self(flags.0 | transfer_id.get())
+    }
+
+    /// Raw tail byte.
+    pub const fn as_u8(&self) -> u8 {
+        self.0
+    }
+
+    /// Start of transfer.
+    pub fn start(&self) -> bool {
+        (self.0 & (1 << 7)) != 0
+    }
+
+    /// End of transfer.
+    pub fn end(&self) -> bool {
+        (self.0 & (1 << 6)) != 0
+    }
+
+    /// Toggle bit (inverts every payload).
+    pub fn toggle(&self) -> bool {
+        (self.0 & (1 << 5)) != 0
+    }
+
+    /// Transfer identifier.
+    pub fn transfer_id(&self) -> u8 {
+        self.0 & 0x1F
+    }
+
+    /// Transfer identifier as a [`TransferId`].
+    pub fn transfer_id_typed(&self) -> TransferId {
+        TransferId::masked(self.0)
+    }
+
+    /// Position of the frame within its transfer.
+    pub fn position(&self) -> FramePosition {
+        match (self.start(), self.end()) {
+            (true, true) => FramePosition::Single,
+            (true, false) => FramePosition::Start,
+            (false, false) => FramePosition::Middle,
+            (false, true) => FramePosition::End,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xorshift::XorShift;
+
+    #[test]
+    fn error_recoverable() {
+        assert!(Error::DataLength.is_fatal());
+        assert!(Error::FrameTooLong.is_fatal());
+        assert!(Error::BufferTooSmall.is_fatal());
+        assert!(Error::EmptyFrame.is_recoverable());
+        assert!(Error::FrameOrder.is_recoverable());
+        assert!(Error::Crc.is_recoverable());
+        assert!(Error::IdMismatch.is_recoverable());
+        assert!(Error::Toggle { frame_index: 0 }.is_recoverable());
+        assert!(Error::FrameFormat.is_recoverable());
+        assert!(Error::TransferTooLarge.is_recoverable());
+        assert!(Error::TooManyFrames.is_recoverable());
+        assert!(
+            Error::TransferIdGap {
+                expected: 1,
+                got: 2
+            }
+            .is_recoverable()
+        );
+    }
+
+    #[test]
+    fn error_kind() {
+        for error in [
+            Error::DataLength,
+            Error::EmptyFrame,
+            Error::FrameOrder,
+            Error::IdMismatch,
+            Error::Toggle { frame_index: 1 },
+            Error::FrameFormat,
+            Error::FrameTooLong,
+        ] {
+            assert_eq!(error.kind(), ErrorKind::Protocol);
+        }
+
+        assert_eq!(Error::BufferTooSmall.kind(), ErrorKind::Resource);
+        assert_eq!(Error::TransferTooLarge.kind(), ErrorKind::Resource);
+        assert_eq!(Error::TooManyFrames.kind(), ErrorKind::Resource);
+
+        assert_eq!(Error::Crc.kind(), ErrorKind::Integrity);
+        let gap = Error::TransferIdGap {
+            expected: 1,
+            got: 2,
+        };
+        assert_eq!(gap.kind(), ErrorKind::Integrity);
+    }
+
+    #[test]
+    fn tail_byte() {
+        let tail = Tail(0xFF);
+        assert!(tail.start());
+        assert!(tail.end());
+        assert!(tail.toggle());
+        assert!(tail.transfer_id() == 31);
+
+        let tail = Tail(0x7C);
+        assert!(!tail.start());
+        assert!(tail.end());
+        assert!(tail.toggle());
+        assert!(tail.transfer_id() == 28)
+    }
+
+    #[test]
+    fn tail_flags() {
+        let tail = Tail::from_flags(TailFlags::START | TailFlags::END, 31);
+        assert_eq!(tail.as_u8(), 0xDF);
+        assert_eq!(tail.position(), FramePosition::Single);
+        assert!(!tail.toggle());
+
+        let mut flags = TailFlags::empty();
+        flags |= TailFlags::TOGGLE;
+        assert!(flags.contains(TailFlags::TOGGLE));
+        assert!(!flags.contains(TailFlags::TOGGLE | TailFlags::END));
+        assert_eq!(Tail::from_flags(flags, 0x3C), Tail::new(0x3C));
+
+        let tail = Tail::from_transfer_id(TailFlags::START, TransferId::MAX);
+        assert_eq!(tail, Tail::new(0x9F));
+        assert_eq!(tail.transfer_id_typed(), TransferId::MAX);
+    }
+
+    #[test]
     fn transfer_single() {
         // 4-byte transfer
         let mut transfer = Transfer::new(vec![]);
-        let res = transfer.add_frame(&[0x01, 0x02, 0x03, 0x04, 0xFF]);
+        let res = transfer.add_frame(&[0x01, 0x02, 0x03, 0x04, 0xDF]);
         assert_eq!(res, Ok(Some([0x01, 0x02, 0x03, 0x04].as_ref())));
 
         // 7-byte transfer
         let mut transfer = Transfer::new(vec![]);
-        let res = transfer.add_frame(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0xFF]);
+        let res = transfer.add_frame(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0xDF]);
         assert_eq!(
             res,
             Ok(Some([0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07].as_ref()))
         );
     }
 
+    /// Every frame length and tail byte with pseudo-random contents, fed to
+    /// transfers in each configuration, must return rather than panic.
+    #[test]
+    fn arbitrary_frames() {
+        const SIGNATURE: u64 = 0xD8A7486238EC3AF3;
+        let mut rng = XorShift::new();
+
+        let configs: [fn() -> Transfer<'static>; 4] = [
+            || Transfer::new(vec![]),
+            || Transfer::new(vec![]).with_include_crc(true),
+            || {
+                Transfer::new(vec![])
+                    .with_data_type_signature(SIGNATURE)
+                    .with_single_frame_crc(true)
+                    .with_lenient_toggle(true)
+            },
+            || {
+                Transfer::new(vec![])
+                    .with_data_type_signature(SIGNATURE)
+                    .with_include_crc(true)
+                    .with_max_payload_len(16)
+            },
+        ];
+
+        for config in configs {
+            for len in 1..=8 {
+                for tail in 0..=255 {
+                    let mut frame: Vec<u8> = (1..len).map(|_| rng.next_u8()).collect();
+                    frame.push(tail);
+
+                    let mut transfer = config();
+                    let _ = transfer.ingest(&frame);
+                    let _ = transfer.ingest(&frame);
+
+                    // continue from a valid start frame
+                    let start = Transfer::from_payload(&[0; 12], tail, SIGNATURE);
+                    let mut transfer = config();
+                    let _ = transfer.ingest(&start[0]);
+                    let _ = transfer.ingest(&frame);
+                    let _ = transfer.payload();
+                }
+            }
+
+            // long runs of random frames into one transfer
+            let mut transfer = config();
+            for _ in 0..4096 {
+                let len = 1 + rng.next_u8() as usize % 8;
+                let frame: Vec<u8> = (0..len).map(|_| rng.next_u8()).collect();
+                if transfer.ingest(&frame) != Ok(false) {
+                    transfer = config();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tail_only() {
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(transfer.add_frame(&[0xC0]), Ok(Some([].as_ref())));
+
+        // the empty transfer is complete and not replaced by the next one
+        assert_eq!(transfer.add_frame(&[0x01, 0xC1]), Err(Error::FrameOrder));
+        assert_eq!(transfer.payload(), Some([].as_ref()));
+
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(transfer.add_frame(&[0x80]), Err(Error::FrameOrder));
+
+        // continuations must carry data
+        let mut transfer = Transfer::new(vec![]);
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        assert_eq!(transfer.add_frame(&[0x3D]), Err(Error::FrameOrder));
+        assert_eq!(transfer.add_frame(&[0x7D]), Err(Error::FrameOrder));
+    }
+
+    #[test]
+    fn builder() {
+        let signature = 0xD8A7486238EC3AF3;
+        let builder = Transfer::builder()
+            .max_frame_len(8)
+            .max_payload_len(64)
+            .max_frames(4)
+            .initial_toggle(true)
+            .frame_tracking(true)
+            .lenient_toggle(true)
+            .data_type_signature(signature)
+            .single_frame_crc(true)
+            .include_crc(true)
+            .auto_restart(true);
+
+        let mut transfer = builder.build(vec![]);
+        let res = transfer.add_frames(&[
+            &[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D],
+            &[0x00, 0x7D, 0x33, 0x7D],
+        ]);
+        let data = &[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+        assert_eq!(res, Ok(Some(data.as_ref())));
+        assert_eq!(transfer.crc_ok(), Some(true));
+        assert_eq!(transfer.frames().count(), 2);
+
+        // the same builder configures any number of transfers
+        let mut storage = [0; 16];
+        let mut transfer = builder.max_frames(1).build(storage.as_mut_slice());
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        assert_eq!(
+            transfer.add_frame(&[0x00, 0x7D, 0x33, 0x7D]),
+            Err(Error::TooManyFrames)
+        );
+
+        assert_eq!(TransferBuilder::default(), Transfer::builder());
+    }
+
+    #[test]
+    fn transfer_default() {
+        let frame = [0x01, 0x02, 0x03, 0x04, 0xDF];
+
+        let mut default = Transfer::default();
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(default.add_frame(&frame), transfer.add_frame(&frame));
+        assert_eq!(default.payload(), Some([0x01, 0x02, 0x03, 0x04].as_ref()));
+    }
+
     #[test]
     fn tansfer_multi() {
         let mut transfer = Transfer::new(vec![]);
@@ -207,12 +1508,707 @@ mod tests {
     }
 
     #[test]
-    fn tansfer_buffer_too_small() {
-        let mut storage = [0; 7]; // 1 byte too small
-        let mut transfer = Transfer::new(storage.as_mut_slice());
-        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+    fn transfer_multi_frames() {
+        let mut transfer = Transfer::new(vec![]);
+        let res = transfer.add_frames(&[
+            &[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D],
+            &[0x00, 0x7D, 0x33, 0x7D],
+        ]);
+        let data = &[0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+        assert_eq!(res, Ok(Some(data.as_ref())));
+
+        let mut transfer = Transfer::new(vec![]);
+        let res = transfer.add_frames(&[&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]]);
         assert_eq!(res, Ok(None));
-        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x7D]);
+        let res = transfer.add_frames(&[&[0x00, 0x7D, 0x33, 0x7D], &[]]);
+        assert_eq!(res, Ok(Some(data.as_ref())));
+    }
+
+    #[test]
+    fn into_payload() {
+        let mut transfer = Transfer::new(vec![]);
+        let res = transfer.add_frames(&[
+            &[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D],
+            &[0x00, 0x7D, 0x33, 0x7D],
+        ]);
+        assert!(matches!(res, Ok(Some(_))));
+
+        let data = [0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+        assert_eq!(transfer.into_payload(), data);
+
+        // borrowed storage is copied
+        let mut storage = [0; 16];
+        let mut transfer = Transfer::new(storage.as_mut_slice());
+        assert!(transfer.add_frame(&[0x01, 0x02, 0xC0]).is_ok());
+        assert_eq!(transfer.into_payload(), [0x01, 0x02]);
+    }
+
+    #[test]
+    fn streaming_sink() {
+        let signature = 0xD8A7486238EC3AF3;
+        let mut received = vec![];
+        let mut sink = |data: &[u8]| received.extend_from_slice(data);
+
+        let mut transfer = Transfer::new([].as_mut_slice())
+            .with_data_type_signature(signature)
+            .with_sink(&mut sink);
+        let res = transfer.add_frames(&[
+            &[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D],
+            &[0x00, 0x7D, 0x33, 0x7D],
+        ]);
+        assert_eq!(res, Ok(Some([].as_ref())));
+        assert!(transfer.completed());
+        drop(transfer);
+
+        assert_eq!(received, [0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33]);
+
+        // corrupted payload fails the crc check on the end frame
+        let mut sink = |_: &[u8]| {};
+        let mut transfer = Transfer::new([].as_mut_slice())
+            .with_data_type_signature(signature)
+            .with_sink(&mut sink);
+        let res = transfer.add_frames(&[
+            &[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D],
+            &[0x00, 0x7D, 0x34, 0x7D],
+        ]);
+        assert_eq!(res, Err(Error::Crc));
+        assert!(!transfer.completed());
+    }
+
+    #[test]
+    fn max_payload_len() {
+        let mut transfer = Transfer::new(vec![]).with_max_payload_len(10);
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x01, 0x02, 0x03, 0x04, 0x3D]);
+        assert_eq!(res, Err(Error::TransferTooLarge));
+        assert_eq!(transfer.data().len(), 5);
+
+        // frames within the limit are still accepted
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x01, 0x02, 0x3D]);
+        assert_eq!(res, Ok(None));
+
+        // a rejected start frame doesn't start a transfer
+        let mut transfer = Transfer::new(vec![]).with_max_payload_len(4);
+        let res = transfer.add_frame(&[0x01, 0x02, 0x01, 0x02, 0x03, 0x04, 0x05, 0x81]);
+        assert_eq!(res, Err(Error::TransferTooLarge));
+        assert_eq!(transfer.add_frame(&[0x09, 0x41]), Err(Error::FrameOrder));
+        assert_eq!(transfer.received_crc(), None);
+    }
+
+    #[test]
+    fn default_max_payload_len() {
+        let frames = Transfer::from_payload(&[0x55; 2000], 0, 0);
+        let frames: Vec<&[u8]> = frames.iter().map(|frame| frame.as_slice()).collect();
+
+        let mut transfer = Transfer::new(vec![]);
+        let res = transfer.add_frames(&frames);
+        assert_eq!(res, Err(Error::TransferTooLarge));
+
+        // borrowed storage is only limited by its length
+        let mut storage = [0; 2000];
+        let mut transfer = Transfer::builder().build(storage.as_mut_slice());
+        let res = transfer.add_frames(&frames);
+        assert_eq!(res, Ok(Some([0x55; 2000].as_ref())));
+    }
+
+    #[test]
+    fn auto_restart() {
+        let frames = Transfer::from_payload(&[0x55; 20], 3, 0);
+
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(transfer.add_frame(&frames[0]), Ok(None));
+        assert_eq!(transfer.add_frame(&frames[1]), Ok(None));
+        assert_eq!(transfer.add_frame(&frames[0]), Err(Error::FrameOrder));
+
+        // the sender retries the transfer from the start
+        let mut transfer = Transfer::new(vec![]).with_auto_restart(true);
+        assert_eq!(transfer.add_frame(&frames[0]), Ok(None));
+        assert_eq!(transfer.add_frame(&frames[1]), Ok(None));
+        for frame in &frames[..frames.len() - 1] {
+            assert_eq!(transfer.add_frame(frame), Ok(None));
+        }
+        let res = transfer.add_frame(&frames[frames.len() - 1]);
+        assert_eq!(res, Ok(Some([0x55; 20].as_ref())));
+
+        // rejected start frames don't abandon the transfer in progress
+        let mut storage = [0; 20];
+        let mut transfer = Transfer::new(storage.as_mut_slice())
+            .with_auto_restart(true)
+            .with_max_frame_len(64);
+        assert_eq!(transfer.add_frame(&frames[0]), Ok(None));
+        let res = transfer.add_frame(&[0x01, 0xE0]);
+        assert_eq!(res, Err(Error::Toggle { frame_index: 0 }));
+        let mut big = [0x66; 25];
+        big[24] = 0xC0;
+        assert_eq!(transfer.add_frame(&big), Err(Error::BufferTooSmall));
+        let mut transfer = transfer.with_max_payload_len(20);
+        assert_eq!(transfer.add_frame(&big), Err(Error::TransferTooLarge));
+        for frame in &frames[1..frames.len() - 1] {
+            assert_eq!(transfer.add_frame(frame), Ok(None));
+        }
+        let res = transfer.add_frame(&frames[frames.len() - 1]);
+        assert_eq!(res, Ok(Some([0x55; 20].as_ref())));
+    }
+
+    #[test]
+    fn crc_ok() {
+        let signature = 0xD8A7486238EC3AF3;
+        let start = [0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D];
+
+        let mut transfer = Transfer::new(vec![]).with_data_type_signature(signature);
+        assert!(transfer.add_frame(&start).is_ok());
+        assert_eq!(transfer.crc_ok(), None);
+        assert!(transfer.add_frame(&[0x00, 0x7D, 0x33, 0x7D]).is_ok());
+        assert_eq!(transfer.crc_ok(), Some(true));
+
+        let mut transfer = Transfer::new(vec![]).with_data_type_signature(signature);
+        assert!(transfer.add_frame(&start).is_ok());
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x34, 0x7D]);
+        assert_eq!(res, Err(Error::Crc));
+        assert_eq!(transfer.crc_ok(), Some(false));
+
+        // not verified without a signature
+        let mut transfer = Transfer::new(vec![]);
+        assert!(
+            transfer
+                .add_frames(&[&start, &[0x00, 0x7D, 0x34, 0x7D]])
+                .is_ok()
+        );
+        assert_eq!(transfer.crc_ok(), None);
+    }
+
+    #[test]
+    fn max_frames() {
+        let mut transfer = Transfer::new(vec![]).with_max_frames(3);
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x9D]);
+        assert_eq!(res, Ok(None));
+        assert_eq!(transfer.add_frame(&[0x02, 0x3D]), Ok(None));
+        assert_eq!(transfer.add_frame(&[0x03, 0x1D]), Ok(None));
+
+        // frames of other transfers don't count against the limit
+        assert_eq!(transfer.add_frame(&[0x04, 0x3E]), Err(Error::IdMismatch));
+        let res = transfer.add_frame(&[0x04, 0x1D]);
+        assert_eq!(res, Err(Error::Toggle { frame_index: 3 }));
+        assert_eq!(transfer.data(), [0x01, 0x02, 0x03]);
+
+        assert_eq!(transfer.add_frame(&[0x04, 0x3D]), Err(Error::TooManyFrames));
+
+        // the abandoned transfer is back to idle
+        assert!(transfer.data().is_empty());
+        assert_eq!(transfer.received_crc(), None);
+        assert_eq!(transfer.add_frame(&[0x05, 0x5D]), Err(Error::FrameOrder));
+        let res = transfer.add_frame(&[0x06, 0xC0]);
+        assert_eq!(res, Ok(Some([0x06].as_ref())));
+    }
+
+    #[test]
+    fn max_frames_start() {
+        let mut transfer = Transfer::new(vec![]).with_max_frames(0);
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x9D]);
+        assert_eq!(res, Err(Error::TooManyFrames));
+        assert_eq!(transfer.received_crc(), None);
+        assert!(transfer.data().is_empty());
+    }
+
+    #[test]
+    fn include_crc() {
+        let mut transfer = Transfer::new(vec![]).with_include_crc(true);
+        let res = transfer.add_frames(&[
+            &[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D],
+            &[0x00, 0x7D, 0x33, 0x7D],
+        ]);
+        let data = &[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+        assert_eq!(res, Ok(Some(data.as_ref())));
+        assert_eq!(transfer.received_crc(), Some(0x9801));
+
+        // single frames have no crc to keep
+        let mut transfer = Transfer::new(vec![]).with_include_crc(true);
+        let res = transfer.add_frame(&[0x01, 0x02, 0xC0]);
+        assert_eq!(res, Ok(Some([0x01, 0x02].as_ref())));
+    }
+
+    #[test]
+    fn try_clone() {
+        let mut transfer = Transfer::new(vec![]);
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+
+        let copy = transfer.try_clone();
+        assert!(copy.is_some());
+        let Some(mut copy) = copy else { return };
+
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x7D]);
+        let data = &[0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+        assert_eq!(res, Ok(Some(data.as_ref())));
+
+        // the copy continues independently of the original
+        let res = copy.add_frame(&[0x01, 0x7D]);
+        let data = &[0x01, 0x00, 0x68, 0xB5, 0x02, 0x01];
+        assert_eq!(res, Ok(Some(data.as_ref())));
+
+        // borrowed storage can't be cloned
+        let mut storage = [0; 16];
+        let transfer = Transfer::new(storage.as_mut_slice());
+        assert!(transfer.try_clone().is_none());
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn into_bytes() {
+        let mut transfer = Transfer::new(vec![]);
+        let res = transfer.add_frames(&[
+            &[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D],
+            &[0x00, 0x7D, 0x33, 0x7D],
+        ]);
+        assert!(matches!(res, Ok(Some(_))));
+
+        let bytes = transfer.into_bytes();
+        let copy = bytes.clone();
+        assert_eq!(
+            copy,
+            [0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33].as_ref()
+        );
+        assert_eq!(bytes.as_ptr(), copy.as_ptr());
+    }
+
+    #[test]
+    fn from_payload() {
+        let signature = 0xD8A7486238EC3AF3;
+
+        let payload: Vec<u8> = (0..40).collect();
+        let frames = Transfer::from_payload(&payload, 29, signature);
+        assert_eq!(frames.len(), frame_count(payload.len()));
+
+        let frames: Vec<&[u8]> = frames.iter().map(|f| f.as_slice()).collect();
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(transfer.add_frames(&frames), Ok(Some(payload.as_slice())));
+
+        // matches the captured ArrayCommand frames
+        let payload = [0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+        let frames = Transfer::from_payload(&payload, 29, signature);
+        assert_eq!(
+            frames,
+            [
+                vec![0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D],
+                vec![0x00, 0x7D, 0x33, 0x7D],
+            ]
+        );
+
+        let frames = Transfer::from_payload(&[0x01, 0x02], 29, signature);
+        assert_eq!(frames, [vec![0x01, 0x02, 0xDD]]);
+    }
+
+    #[test]
+    fn frame_tracking() {
+        let mut transfer = Transfer::new(vec![]).with_frame_tracking(true);
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x7D]);
+        assert!(res.is_ok());
+
+        let frames: Vec<_> = transfer.frames().collect();
+        assert_eq!(
+            frames,
+            [[0x01, 0x00, 0x68, 0xB5, 0x02].as_ref(), &[0x00, 0x7D, 0x33]]
+        );
+
+        // frames accepted before an error remain
+        let mut transfer = Transfer::new(vec![]).with_frame_tracking(true);
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x5D]);
+        assert_eq!(res, Err(Error::Toggle { frame_index: 1 }));
+        assert_eq!(transfer.frames().count(), 1);
+
+        let mut transfer = Transfer::new(vec![]);
+        assert!(transfer.add_frame(&[0x01, 0xC0]).is_ok());
+        assert_eq!(transfer.frames().count(), 0);
+    }
+
+    #[test]
+    fn add_can_frame() {
+        use crate::mock::MockFrame;
+
+        let mut transfer = Transfer::new(vec![]);
+        let start = MockFrame::extended(
+            0x0803F20A,
+            &[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D],
+        );
+        assert_eq!(transfer.add_can_frame(&start), Ok(None));
+
+        let standard = MockFrame::standard(0x123, &[0x00, 0x7D, 0x33, 0x7D]);
+        assert_eq!(transfer.add_can_frame(&standard), Err(Error::FrameFormat));
+        let remote = MockFrame::remote(0x0803F20A, 4);
+        assert_eq!(transfer.add_can_frame(&remote), Err(Error::FrameFormat));
+        let other = MockFrame::extended(0x0803F20B, &[0x00, 0x7D, 0x33, 0x7D]);
+        assert_eq!(transfer.add_can_frame(&other), Err(Error::IdMismatch));
+
+        let end = MockFrame::extended(0x0803F20A, &[0x00, 0x7D, 0x33, 0x7D]);
+        let data = &[0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+        assert_eq!(transfer.add_can_frame(&end), Ok(Some(data.as_ref())));
+    }
+
+    #[test]
+    fn received_crc() {
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(transfer.received_crc(), None);
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        assert_eq!(transfer.received_crc(), Some(0x9801));
+
+        let mut transfer = Transfer::new(vec![]);
+        assert!(transfer.add_frame(&[0x01, 0x02, 0xC0]).is_ok());
+        assert_eq!(transfer.received_crc(), None);
+    }
+
+    #[test]
+    fn completed() {
+        let mut transfer = Transfer::new(vec![]);
+        assert!(!transfer.completed());
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        assert!(!transfer.completed());
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x5D]);
+        assert_eq!(res, Err(Error::Toggle { frame_index: 1 }));
+        assert!(!transfer.completed());
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x7D]);
+        assert!(res.is_ok());
+        assert!(transfer.completed());
+    }
+
+    #[test]
+    fn toggle_frame_index() {
+        let mut transfer = Transfer::new(vec![]);
+        let res = transfer.add_frames(&[
+            &[0x01, 0x98, 0x01, 0x00, 0x68, 0x9D],
+            &[0xB5, 0x02, 0x3D],
+            &[0x00, 0x7D, 0x33, 0x7D],
+        ]);
+        assert_eq!(res, Err(Error::Toggle { frame_index: 2 }));
+        assert_eq!(
+            res.map_err(|e| e.to_string()),
+            Err("toggle bit incorrect at frame 2".into())
+        );
+    }
+
+    #[test]
+    fn observer() {
+        let mut events = Vec::new();
+        let mut observer = |event| events.push(event);
+
+        let mut transfer = Transfer::new(vec![]).with_observer(&mut observer);
+        let res = transfer.add_frames(&[
+            &[0x01, 0x98, 0x01, 0x00, 0x68, 0x9D],
+            &[0xB5, 0x02, 0x3D],
+            &[0xB5, 0x02, 0x3D], // rejected
+            &[0x00, 0x7D, 0x33, 0x5D],
+        ]);
+        assert_eq!(res, Err(Error::Toggle { frame_index: 2 }));
+        assert!(transfer.add_frame(&[0x00, 0x7D, 0x33, 0x5D]).is_ok());
+        drop(transfer);
+
+        let positions: Vec<_> = events.iter().map(|e| (e.position, e.toggle)).collect();
+        assert_eq!(
+            positions,
+            [
+                (FramePosition::Start, false),
+                (FramePosition::Middle, true),
+                (FramePosition::End, false),
+            ]
+        );
+        assert!(events.iter().all(|e| e.transfer_id == 29));
+        assert_eq!(events.iter().map(|e| e.len).sum::<usize>(), 8);
+    }
+
+    #[test]
+    fn payload() {
+        let data = &[0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(transfer.payload(), None);
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        assert_eq!(transfer.payload(), None);
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x7D]);
+        assert!(res.is_ok());
+
+        assert_eq!(transfer.payload(), Some(data.as_ref()));
+        assert_eq!(transfer.payload(), Some(data.as_ref()));
+    }
+
+    #[test]
+    fn initial_toggle() {
+        let mut transfer = Transfer::new(vec![]).with_initial_toggle(true);
+        let res = transfer.add_frame(&[0x01, 0x02, 0x20]);
+        assert_eq!(res, Ok(None));
+        let res = transfer.add_frame(&[0x03, 0x40]);
+        assert_eq!(res, Ok(Some([0x01, 0x02, 0x03].as_ref())));
+
+        // joined on an odd frame
+        let mut transfer = Transfer::new(vec![]).with_initial_toggle(false);
+        let res = transfer.add_frame(&[0x01, 0x02, 0x00]);
+        assert_eq!(res, Ok(None));
+        let res = transfer.add_frame(&[0x03, 0x60]);
+        assert_eq!(res, Ok(Some([0x01, 0x02, 0x03].as_ref())));
+
+        let mut transfer = Transfer::new(vec![]);
+        let res = transfer.add_frame(&[0x01, 0x02, 0x00]);
+        assert_eq!(res, Err(Error::Toggle { frame_index: 0 }));
+    }
+
+    #[test]
+    fn single_frame_crc() {
+        let frame = [0x1D, 0x94, 0x01, 0x00, 0x68, 0xB5, 0xDD];
+        let signature = 0xD8A7486238EC3AF3;
+
+        let mut transfer = Transfer::new(vec![])
+            .with_data_type_signature(signature)
+            .with_single_frame_crc(true);
+        let res = transfer.add_frame(&frame);
+        assert_eq!(res, Ok(Some([0x01, 0x00, 0x68, 0xB5].as_ref())));
+
+        // CRC doesn't match, treated as payload
+        let mut transfer = Transfer::new(vec![])
+            .with_data_type_signature(signature)
+            .with_single_frame_crc(true);
+        let res = transfer.add_frame(&[0x1D, 0x95, 0x01, 0x00, 0x68, 0xB5, 0xDD]);
+        assert_eq!(res, Ok(Some([0x1D, 0x95, 0x01, 0x00, 0x68, 0xB5].as_ref())));
+
+        // strict by default
+        let mut transfer = Transfer::new(vec![]).with_data_type_signature(signature);
+        let res = transfer.add_frame(&frame);
+        assert_eq!(res, Ok(Some(&frame[..6])));
+    }
+
+    #[test]
+    fn with_capacity() {
+        let mut storage = [0; 16];
+        assert!(Transfer::with_capacity(&mut storage, 17).is_none());
+
+        let transfer = Transfer::with_capacity(&mut storage, 7);
+        assert!(transfer.is_some());
+        let Some(mut transfer) = transfer else { return };
+
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x7D]);
+        assert_eq!(res, Err(Error::BufferTooSmall));
+    }
+
+    #[test]
+    fn tansfer_buffer_too_small() {
+        let mut storage = [0; 7]; // 1 byte too small
+        let mut transfer = Transfer::new(storage.as_mut_slice());
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x7D]);
+        assert_eq!(res, Err(Error::BufferTooSmall));
+    }
+
+    #[test]
+    fn single_frame_toggle() {
+        let frame = [0x01, 0x02, 0xE0 | 0x1F];
+
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(
+            transfer.add_frame(&frame),
+            Err(Error::Toggle { frame_index: 0 })
+        );
+        assert_eq!(decode_single(&frame), Err(Error::Toggle { frame_index: 0 }));
+
+        let mut transfer = Transfer::new(vec![]).with_lenient_toggle(true);
+        assert_eq!(transfer.add_frame(&frame), Ok(Some([0x01, 0x02].as_ref())));
+    }
+
+    #[test]
+    fn set_storage() {
+        let mut small = [0; 4];
+        let mut transfer = Transfer::new(small.as_mut_slice());
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x9D]);
+        assert_eq!(res, Ok(None));
+        let res = transfer.add_frame(&[0x02, 0x00, 0x7D, 0x33, 0x7D]);
+        assert_eq!(res, Err(Error::BufferTooSmall));
+
+        let mut tiny = [0; 3];
+        assert_eq!(
+            transfer.set_storage(tiny.as_mut_slice()),
+            Err(Error::BufferTooSmall)
+        );
+
+        let mut large = [0; 16];
+        assert_eq!(transfer.set_storage(large.as_mut_slice()), Ok(()));
+        let res = transfer.add_frame(&[0x02, 0x00, 0x7D, 0x33, 0x7D]);
+        let data = &[0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+        assert_eq!(res, Ok(Some(data.as_ref())));
+
+        // into owned storage
+        let mut small = [0; 4];
+        let mut transfer = Transfer::new(small.as_mut_slice());
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x9D]);
+        assert_eq!(res, Ok(None));
+        assert_eq!(transfer.set_storage(vec![0xAA; 2]), Ok(()));
+        let res = transfer.add_frame(&[0x02, 0x00, 0x7D, 0x33, 0x7D]);
+        assert_eq!(res, Ok(Some(data.as_ref())));
+    }
+
+    #[test]
+    fn single_frame_fast_path() {
+        let res = decode_single(&[0x01, 0x02, 0x03, 0x04, 0xDF]);
+        assert_eq!(res, Ok([0x01, 0x02, 0x03, 0x04].as_ref()));
+
+        let res = decode_single(&[0xC0]);
+        assert_eq!(res, Ok([].as_ref()));
+
+        // start of a multi-frame transfer
+        let res = decode_single(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Err(Error::FrameOrder));
+
+        assert_eq!(decode_single(&[]), Err(Error::EmptyFrame));
+    }
+
+    #[test]
+    fn finish_partial() {
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(transfer.finish(), Err(Error::FrameOrder));
+
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        assert_eq!(
+            transfer.finish(),
+            Ok([0x01, 0x00, 0x68, 0xB5, 0x02].as_ref())
+        );
+    }
+
+    #[test]
+    fn max_frame_len() {
+        let mut frame = [0xAA; 20];
+        frame[19] = 0xC0;
+
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(transfer.add_frame(&frame), Err(Error::FrameTooLong));
+
+        let mut transfer = Transfer::new(vec![]).with_max_frame_len(64);
+        assert_eq!(transfer.add_frame(&frame), Ok(Some([0xAA; 19].as_ref())));
+    }
+
+    #[test]
+    fn frame_too_long() {
+        let frame = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0xC0];
+
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(transfer.add_frame(&frame), Err(Error::FrameTooLong));
+        assert_eq!(decode_single(&frame), Err(Error::FrameTooLong));
+    }
+
+    #[test]
+    fn start_frame_too_short() {
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(transfer.add_frame(&[0x01, 0x9D]), Err(Error::DataLength));
+        // a tail byte alone is never a valid start frame
+        assert_eq!(transfer.add_frame(&[0x9D]), Err(Error::FrameOrder));
+
+        // shortest start frame only has the crc
+        let res = transfer.add_frame(&[0x01, 0x98, 0x9D]);
+        assert_eq!(res, Ok(None));
+        assert_eq!(transfer.received_crc(), Some(0x9801));
+        assert_eq!(transfer.add_frame(&[0x01, 0x7D]), Ok(Some([0x01].as_ref())));
+    }
+
+    #[test]
+    fn frame_counts() {
+        assert_eq!(frame_count(0), 1);
+        assert_eq!(frame_count(7), 1);
+        assert_eq!(frame_count(8), 2);
+        assert_eq!(frame_count(12), 2);
+        assert_eq!(frame_count(13), 3);
+        assert_eq!(frame_count(63), 10);
+        assert_eq!(frame_count(64), 10);
+
+        assert_eq!(frame_count_with_max(63, 64), 1);
+        assert_eq!(frame_count_with_max(64, 64), 2);
+    }
+
+    #[test]
+    fn empty_frame() {
+        let mut transfer = Transfer::new(vec![]);
+        assert_eq!(transfer.add_frame(&[]), Err(Error::EmptyFrame));
+        assert_eq!(Error::EmptyFrame.to_string(), "frame is empty");
+    }
+
+    #[test]
+    fn debug_preview() {
+        let mut transfer = Transfer::new(vec![]);
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x7D]);
+        assert!(res.is_ok());
+
+        assert_eq!(
+            format!("{:?}", transfer),
+            "Transfer { len: 8, transfer_id: 29, toggle: true, data: [01, 00, 68, b5, 02, 00, 7d, 33], .. }"
+        );
+    }
+
+    #[test]
+    fn transfer_with_id() {
+        let a = Id::new(0x0803F20A);
+        let b = Id::new(0x0803F20B); // different source node
+
+        let mut transfer = Transfer::new(vec![]);
+        let res = transfer.add_frame_with_id(a, &[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        let res = transfer.add_frame_with_id(b, &[0x00, 0x7D, 0x33, 0x7D]);
+        assert_eq!(res, Err(Error::IdMismatch));
+        let res = transfer.add_frame_with_id(a, &[0x00, 0x7D, 0x33, 0x7D]);
+        let data = &[0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+        assert_eq!(res, Ok(Some(data.as_ref())));
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn transfer_single_heapless() {
+        // 4-byte transfer
+        let mut transfer = Transfer::new(heapless::Vec::<u8, 8>::new());
+        let res = transfer.add_frame(&[0x01, 0x02, 0x03, 0x04, 0xDF]);
+        assert_eq!(res, Ok(Some([0x01, 0x02, 0x03, 0x04].as_ref())));
+
+        // 7-byte transfer
+        let mut transfer = Transfer::new(heapless::Vec::<u8, 8>::new());
+        let res = transfer.add_frame(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0xDF]);
+        assert_eq!(
+            res,
+            Ok(Some([0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07].as_ref()))
+        );
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn tansfer_multi_heapless() {
+        let mut transfer = Transfer::new(heapless::Vec::<u8, 8>::new());
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x7D]);
+        let data = &[0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+        assert_eq!(res, Ok(Some(data.as_ref())));
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn tansfer_buffer_too_small_heapless() {
+        let mut transfer = Transfer::new(heapless::Vec::<u8, 7>::new()); // 1 byte too small
+        let res = transfer.add_frame(&[0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D]);
+        assert_eq!(res, Ok(None));
+        let res = transfer.add_frame(&[0x00, 0x7D, 0x33, 0x7D]);
         assert_eq!(res, Err(Error::BufferTooSmall));
     }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn into_heapless_payload() {
+        let mut transfer = Transfer::new(heapless::Vec::<u8, 8>::new());
+        assert!(transfer.add_frame(&[0x01, 0x02, 0xC0]).is_ok());
+        let payload = transfer.into_heapless_payload();
+        assert_eq!(payload.as_deref(), Ok([0x01, 0x02].as_ref()));
+    }
 }
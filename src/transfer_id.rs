@@ -0,0 +1,91 @@
+use core::fmt;
+
+/// Transfer identifier carried in the tail byte, validated to `0..=31`.
+///
+/// Builds tail bytes with [`crate::Tail::from_transfer_id`] and is read back
+/// with [`crate::Tail::transfer_id_typed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TransferId(u8);
+
+impl TransferId {
+    /// Highest transfer ID before wrapping around to zero.
+    pub const MAX: TransferId = TransferId(31);
+
+    /// Create a transfer ID, returning `None` if it is larger than 31.
+    pub const fn new(value: u8) -> Option<Self> {
+        if value > 0x1F {
+            None
+        } else {
+            Some(Self(value))
+        }
+    }
+
+    /// Transfer ID of the five lowest bits of `value`.
+    pub(crate) const fn masked(value: u8) -> Self {
+        Self(value & 0x1F)
+    }
+
+    /// Raw transfer ID value.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    /// Transfer ID of the next transfer, wrapping from 31 to 0.
+    #[must_use]
+    pub const fn next(self) -> Self {
+        Self((self.0 + 1) & 0x1F)
+    }
+}
+
+impl TryFrom<u8> for TransferId {
+    type Error = InvalidTransferId;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value).ok_or(InvalidTransferId)
+    }
+}
+
+impl From<TransferId> for u8 {
+    fn from(value: TransferId) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for TransferId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Error converting a value larger than 31 into a [`TransferId`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InvalidTransferId;
+
+impl fmt::Display for InvalidTransferId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid transfer id")
+    }
+}
+
+impl core::error::Error for InvalidTransferId {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range() {
+        assert_eq!(TransferId::try_from(0), Ok(TransferId::default()));
+        assert_eq!(TransferId::try_from(31), Ok(TransferId::MAX));
+        assert_eq!(TransferId::try_from(32), Err(InvalidTransferId));
+        assert_eq!(TransferId::try_from(255), Err(InvalidTransferId));
+    }
+
+    #[test]
+    fn next() {
+        assert_eq!(TransferId::new(4).map(TransferId::next), TransferId::new(5));
+        assert_eq!(TransferId::MAX.next(), TransferId::default());
+    }
+}
@@ -0,0 +1,190 @@
+#[cfg(feature = "heapless")]
+use crate::Id;
+use crate::dsdl::{BitReader, BitWriter, Error};
+
+/// Dynamic node ID allocation, `uavcan.protocol.dynamic_node_id.Allocation`.
+///
+/// Allocatees broadcast anonymous requests carrying parts of their unique ID
+/// and the allocator responds with the unique ID received so far, followed
+/// by the allocated node ID once the whole unique ID has been received.
+///
+/// [Reference](https://dronecan.github.io/Specification/7._List_of_standard_data_types/#allocation)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Allocation<'a> {
+    /// Requested or allocated node ID, zero for any or while the allocation is
+    /// in progress.
+    pub node_id: u8,
+    /// Is this the first part of the unique ID? Only set by allocatees.
+    pub first_part_of_unique_id: bool,
+    /// Unique ID or part of it.
+    pub unique_id: &'a [u8],
+}
+
+impl<'a> Allocation<'a> {
+    /// Default data type ID.
+    pub const TYPE_ID: u16 = 1;
+
+    /// Data type signature.
+    pub const SIGNATURE: u64 = 0x0B2A812620A11D40;
+
+    /// Maximum length of the unique ID in bytes.
+    pub const MAX_UNIQUE_ID_LEN: usize = 16;
+
+    /// Maximum length of the unique ID in an anonymous request, which has to
+    /// fit in a single frame.
+    pub const MAX_LENGTH_OF_UNIQUE_ID_IN_REQUEST: usize = 6;
+
+    /// Decode from a transfer payload.
+    pub fn decode(payload: &'a [u8]) -> Result<Self, Error> {
+        let mut reader = BitReader::new(payload);
+
+        let node_id = reader.read_u8(7)?;
+        let first_part_of_unique_id = reader.read_bool()?;
+
+        // tail array optimization, unique ID takes up the rest of the payload
+        let unique_id = reader.read_remaining_bytes()?;
+        if unique_id.len() > Self::MAX_UNIQUE_ID_LEN {
+            return Err(Error::ArrayLength);
+        }
+
+        Ok(Self {
+            node_id,
+            first_part_of_unique_id,
+            unique_id,
+        })
+    }
+
+    /// Encode into `buf`, returning the number of bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        if self.node_id > 0x7F {
+            return Err(Error::InvalidValue);
+        }
+
+        if self.unique_id.len() > Self::MAX_UNIQUE_ID_LEN {
+            return Err(Error::ArrayLength);
+        }
+
+        let mut writer = BitWriter::new(buf);
+
+        writer.write_u8(self.node_id, 7)?;
+        writer.write_bool(self.first_part_of_unique_id)?;
+        writer.write_bytes(self.unique_id)?;
+
+        Ok(writer.len())
+    }
+}
+
+/// Build the anonymous first-stage allocation request.
+///
+/// Returns the identifier and single-frame payload requesting
+/// `preferred_node`, or zero for any, with the first
+/// [`Allocation::MAX_LENGTH_OF_UNIQUE_ID_IN_REQUEST`] bytes of `unique_id`.
+/// The request is sent at [`Id::NOMINAL_PRIORITY`].
+///
+/// Returns `None` if `preferred_node` is larger than 127 or `discriminator`
+/// is not valid for [`Id::anonymous`].
+#[cfg(feature = "heapless")]
+pub fn allocation_request_stage1(
+    preferred_node: u8,
+    unique_id: &[u8; 16],
+    discriminator: u16,
+) -> Option<(Id, heapless::Vec<u8, 8>)> {
+    let id = Id::anonymous(Allocation::TYPE_ID, discriminator, Id::NOMINAL_PRIORITY)?;
+
+    let allocation = Allocation {
+        node_id: preferred_node,
+        first_part_of_unique_id: true,
+        unique_id: &unique_id[..Allocation::MAX_LENGTH_OF_UNIQUE_ID_IN_REQUEST],
+    };
+
+    let mut buf = [0; 8];
+    let len = allocation.encode(&mut buf).ok()?;
+    let payload = heapless::Vec::from_slice(&buf[..len]).ok()?;
+
+    Some((id, payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIQUE_ID: [u8; 16] = [
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE,
+        0xFF,
+    ];
+
+    #[test]
+    fn first_stage() {
+        let allocation = Allocation {
+            node_id: 0,
+            first_part_of_unique_id: true,
+            unique_id: &UNIQUE_ID[..Allocation::MAX_LENGTH_OF_UNIQUE_ID_IN_REQUEST],
+        };
+        let payload = [0x01, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+
+        let mut buf = [0; 17];
+        assert_eq!(allocation.encode(&mut buf), Ok(payload.len()));
+        assert_eq!(buf[..payload.len()], payload);
+        assert_eq!(Allocation::decode(&payload), Ok(allocation));
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn request_stage1() {
+        let request = allocation_request_stage1(125, &UNIQUE_ID, 0x1234);
+        let id = Id::Anonymous {
+            priority: Id::NOMINAL_PRIORITY,
+            discriminator: 0x1234,
+            type_id: 1,
+        };
+        let payload = [0xFB, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+
+        let request = request
+            .as_ref()
+            .map(|(id, payload)| (*id, payload.as_slice()));
+        assert_eq!(request, Some((id, payload.as_ref())));
+
+        assert!(allocation_request_stage1(128, &UNIQUE_ID, 0x1234).is_none());
+        assert!(allocation_request_stage1(125, &UNIQUE_ID, 0).is_none());
+    }
+
+    #[test]
+    fn final_stage() {
+        let allocation = Allocation {
+            node_id: 125,
+            first_part_of_unique_id: false,
+            unique_id: &UNIQUE_ID,
+        };
+
+        let mut buf = [0; 17];
+        assert_eq!(allocation.encode(&mut buf), Ok(17));
+        assert_eq!(buf[0], 0xFA);
+        assert_eq!(buf[1..], UNIQUE_ID);
+        assert_eq!(Allocation::decode(&buf), Ok(allocation));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(Allocation::decode(&[]), Err(Error::Truncated));
+        assert_eq!(Allocation::decode(&[0; 18]), Err(Error::ArrayLength));
+
+        let mut buf = [0; 17];
+        let allocation = Allocation {
+            node_id: 128,
+            first_part_of_unique_id: false,
+            unique_id: &[],
+        };
+        assert_eq!(allocation.encode(&mut buf), Err(Error::InvalidValue));
+
+        let allocation = Allocation {
+            node_id: 1,
+            first_part_of_unique_id: false,
+            unique_id: &UNIQUE_ID,
+        };
+        assert_eq!(
+            allocation.encode(&mut buf[..16]),
+            Err(Error::BufferTooSmall)
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Message type IDs of ArduPilot specific data types.
+//!
+//! These live in the vendor-specific range, see
+//! [`Id::VENDOR_MESSAGE_TYPE_IDS`](crate::Id::VENDOR_MESSAGE_TYPE_IDS).
+//!
+//! [Reference](https://github.com/dronecan/DSDL/tree/master/ardupilot)
+//!
+//! ```
+//! use dronecan::{Id, Kind, types::ardupilot};
+//!
+//! let id = Id::new(0x184E270A);
+//! assert!(matches!(
+//!     id.kind(),
+//!     Kind::Message {
+//!         type_id: ardupilot::NOTIFY_STATE
+//!     }
+//! ));
+//! ```
+
+/// `ardupilot.indication.SafetyState`
+pub const SAFETY_STATE: u16 = 20000;
+
+/// `ardupilot.indication.Button`
+pub const BUTTON: u16 = 20001;
+
+/// `ardupilot.gnss.Heading`
+pub const GNSS_HEADING: u16 = 20002;
+
+/// `ardupilot.gnss.Status`
+pub const GNSS_STATUS: u16 = 20003;
+
+/// `ardupilot.equipment.power.BatteryInfoAux`
+pub const BATTERY_INFO_AUX: u16 = 20004;
+
+/// `ardupilot.gnss.MovingBaselineData`
+pub const MOVING_BASELINE_DATA: u16 = 20005;
+
+/// `ardupilot.gnss.RelPosHeading`
+pub const REL_POS_HEADING: u16 = 20006;
+
+/// `ardupilot.indication.NotifyState`
+pub const NOTIFY_STATE: u16 = 20007;
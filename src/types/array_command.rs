@@ -0,0 +1,180 @@
+use crate::dsdl::{BitReader, BitWriter, Error};
+
+/// Single actuator command, `uavcan.equipment.actuator.Command`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Command {
+    /// Actuator ID.
+    pub actuator_id: u8,
+    /// Interpretation of the command value, one of the `COMMAND_TYPE_*`
+    /// constants.
+    pub command_type: u8,
+    /// Command value, serialized as a `float16`.
+    pub command_value: f32,
+}
+
+impl Command {
+    /// Value is unitless in the range `-1..=1`.
+    pub const COMMAND_TYPE_UNITLESS: u8 = 0;
+    /// Position in meters or radians.
+    pub const COMMAND_TYPE_POSITION: u8 = 1;
+    /// Force in newtons or torque in newton meters.
+    pub const COMMAND_TYPE_FORCE: u8 = 2;
+    /// Speed in meters or radians per second.
+    pub const COMMAND_TYPE_SPEED: u8 = 3;
+    /// Pulse width in microseconds.
+    pub const COMMAND_TYPE_PWM: u8 = 4;
+
+    fn decode(reader: &mut BitReader<'_>) -> Result<Self, Error> {
+        Ok(Self {
+            actuator_id: reader.read_u8(8)?,
+            command_type: reader.read_u8(8)?,
+            command_value: reader.read_f16()?,
+        })
+    }
+
+    fn encode(&self, writer: &mut BitWriter<'_>) -> Result<(), Error> {
+        writer.write_u8(self.actuator_id, 8)?;
+        writer.write_u8(self.command_type, 8)?;
+        writer.write_f16(self.command_value)
+    }
+}
+
+/// Commands for several actuators, `uavcan.equipment.actuator.ArrayCommand`.
+///
+/// [Reference](https://dronecan.github.io/Specification/7._List_of_standard_data_types/#arraycommand)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ArrayCommand {
+    commands: [Command; Self::MAX_COMMANDS],
+    len: usize,
+}
+
+impl ArrayCommand {
+    /// Default data type ID.
+    pub const TYPE_ID: u16 = 1010;
+
+    /// Data type signature.
+    pub const SIGNATURE: u64 = 0xD8A7486238EC3AF3;
+
+    /// Maximum number of commands.
+    pub const MAX_COMMANDS: usize = 15;
+
+    /// Serialized length of a command in bytes.
+    const COMMAND_LEN: usize = 4;
+
+    /// Create from a list of commands.
+    ///
+    /// Returns `None` if there are more than [`ArrayCommand::MAX_COMMANDS`].
+    pub fn new(commands: &[Command]) -> Option<Self> {
+        let mut array = Self {
+            commands: [Command::default(); Self::MAX_COMMANDS],
+            len: commands.len(),
+        };
+
+        array
+            .commands
+            .get_mut(..commands.len())?
+            .copy_from_slice(commands);
+        Some(array)
+    }
+
+    /// Commands in the array.
+    pub fn commands(&self) -> &[Command] {
+        &self.commands[..self.len]
+    }
+
+    /// Decode from a transfer payload.
+    pub fn decode(payload: &[u8]) -> Result<Self, Error> {
+        // tail array optimization, commands take up the whole payload
+        if payload.len() % Self::COMMAND_LEN != 0 {
+            return Err(Error::Truncated);
+        }
+
+        let len = payload.len() / Self::COMMAND_LEN;
+        if len > Self::MAX_COMMANDS {
+            return Err(Error::ArrayLength);
+        }
+
+        let mut reader = BitReader::new(payload);
+        let mut commands = [Command::default(); Self::MAX_COMMANDS];
+        for command in commands.iter_mut().take(len) {
+            *command = Command::decode(&mut reader)?;
+        }
+
+        Ok(Self { commands, len })
+    }
+
+    /// Encode into `buf`, returning the number of bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut writer = BitWriter::new(buf);
+
+        for command in self.commands() {
+            command.encode(&mut writer)?;
+        }
+
+        Ok(writer.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Transfer;
+
+    const PAYLOAD: [u8; 8] = [0x01, 0x00, 0x68, 0xB5, 0x02, 0x00, 0x7D, 0x33];
+
+    fn commands() -> [Command; 2] {
+        [
+            Command {
+                actuator_id: 1,
+                command_type: Command::COMMAND_TYPE_UNITLESS,
+                command_value: -0.337_890_63,
+            },
+            Command {
+                actuator_id: 2,
+                command_type: Command::COMMAND_TYPE_UNITLESS,
+                command_value: 0.234_008_79,
+            },
+        ]
+    }
+
+    #[test]
+    fn decode() {
+        let array = ArrayCommand::decode(&PAYLOAD);
+        assert_eq!(
+            array.as_ref().map(|a| a.commands()),
+            Ok(commands().as_ref())
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let array = ArrayCommand::new(&commands());
+        assert!(array.is_some());
+        let Some(array) = array else { return };
+
+        let mut buf = [0; 60];
+        assert_eq!(array.encode(&mut buf), Ok(PAYLOAD.len()));
+        assert_eq!(buf[..PAYLOAD.len()], PAYLOAD);
+        assert_eq!(ArrayCommand::decode(&PAYLOAD), Ok(array));
+
+        // same frames as captured on the bus
+        let frames = Transfer::from_payload(&buf[..PAYLOAD.len()], 29, ArrayCommand::SIGNATURE);
+        assert_eq!(
+            frames,
+            [
+                vec![0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D],
+                vec![0x00, 0x7D, 0x33, 0x7D],
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(ArrayCommand::new(&[Command::default(); 16]), None);
+        assert_eq!(ArrayCommand::decode(&PAYLOAD[..7]), Err(Error::Truncated));
+        assert_eq!(ArrayCommand::decode(&[0; 64]), Err(Error::ArrayLength));
+        assert_eq!(ArrayCommand::decode(&[]).map(|a| a.commands().len()), Ok(0));
+    }
+}
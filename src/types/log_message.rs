@@ -0,0 +1,132 @@
+use crate::dsdl::{BitReader, Error};
+
+/// Log message severity, `uavcan.protocol.debug.LogLevel`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+impl LogLevel {
+    /// Level from its serialized value.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Debug),
+            1 => Some(Self::Info),
+            2 => Some(Self::Warning),
+            3 => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    /// Serialized value of the level.
+    pub fn as_u8(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Human-readable log message, `uavcan.protocol.debug.LogMessage`.
+///
+/// [Reference](https://dronecan.github.io/Specification/7._List_of_standard_data_types/#logmessage)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LogMessage<'a> {
+    level: LogLevel,
+    source: &'a [u8],
+    text: &'a [u8],
+}
+
+impl<'a> LogMessage<'a> {
+    /// Default data type ID.
+    pub const TYPE_ID: u16 = 16383;
+
+    /// Data type signature.
+    pub const SIGNATURE: u64 = 0xD654A48E0C049D75;
+
+    /// Maximum length of the source in bytes.
+    pub const MAX_SOURCE_LEN: usize = 31;
+
+    /// Maximum length of the text in bytes.
+    pub const MAX_TEXT_LEN: usize = 90;
+
+    /// Decode from a transfer payload.
+    pub fn decode(payload: &'a [u8]) -> Result<Self, Error> {
+        let mut reader = BitReader::new(payload);
+
+        let level = LogLevel::from_u8(reader.read_u8(3)?).ok_or(Error::InvalidValue)?;
+        let source_len = reader.read_u8(5)? as usize;
+        let source = reader.read_bytes(source_len)?;
+
+        // tail array optimization, text takes up the rest of the payload
+        let text = reader.read_remaining_bytes()?;
+        if text.len() > Self::MAX_TEXT_LEN {
+            return Err(Error::ArrayLength);
+        }
+
+        Ok(Self {
+            level,
+            source,
+            text,
+        })
+    }
+
+    /// Message severity.
+    pub fn level(&self) -> LogLevel {
+        self.level
+    }
+
+    /// Message source if it is valid UTF-8.
+    pub fn source(&self) -> Option<&'a str> {
+        core::str::from_utf8(self.source).ok()
+    }
+
+    /// Message source as raw bytes.
+    pub fn source_bytes(&self) -> &'a [u8] {
+        self.source
+    }
+
+    /// Message text if it is valid UTF-8.
+    pub fn text(&self) -> Option<&'a str> {
+        core::str::from_utf8(self.text).ok()
+    }
+
+    /// Message text as raw bytes.
+    pub fn text_bytes(&self) -> &'a [u8] {
+        self.text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode() {
+        let payload = b"\x44Ctrlstarting up";
+        let message = LogMessage::decode(payload);
+
+        assert!(message.is_ok());
+        if let Ok(message) = message {
+            assert_eq!(message.level(), LogLevel::Warning);
+            assert_eq!(message.source(), Some("Ctrl"));
+            assert_eq!(message.text(), Some("starting up"));
+        }
+    }
+
+    #[test]
+    fn decode_empty_fields() {
+        let message = LogMessage::decode(&[0x60]).map(|m| (m.level(), m.source(), m.text()));
+        assert_eq!(message, Ok((LogLevel::Error, Some(""), Some(""))));
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(LogMessage::decode(&[]), Err(Error::Truncated));
+        assert_eq!(LogMessage::decode(&[0xE0]), Err(Error::InvalidValue));
+        assert_eq!(LogMessage::decode(b"\x25src"), Err(Error::Truncated));
+        assert_eq!(LogMessage::decode(&[0x00; 92]), Err(Error::ArrayLength));
+    }
+}
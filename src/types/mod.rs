@@ -0,0 +1,16 @@
+//! Standard DroneCAN data types.
+
+mod allocation;
+pub mod ardupilot;
+mod array_command;
+mod log_message;
+mod names;
+mod node_monitor;
+mod node_status;
+
+pub use allocation::*;
+pub use array_command::*;
+pub use log_message::*;
+pub use names::*;
+pub use node_monitor::*;
+pub use node_status::*;
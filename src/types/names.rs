@@ -0,0 +1,75 @@
+use super::{Allocation, ArrayCommand, LogMessage, NodeStatus};
+
+/// Full DSDL names of well-known standard message types and their default
+/// data type IDs.
+const MESSAGE_TYPES: [(&str, u16); 22] = [
+    (
+        "uavcan.protocol.dynamic_node_id.Allocation",
+        Allocation::TYPE_ID,
+    ),
+    ("uavcan.protocol.GlobalTimeSync", 4),
+    ("uavcan.protocol.Panic", 5),
+    ("uavcan.protocol.NodeStatus", NodeStatus::TYPE_ID),
+    ("uavcan.protocol.debug.KeyValue", 16370),
+    ("uavcan.protocol.debug.LogMessage", LogMessage::TYPE_ID),
+    ("uavcan.equipment.ahrs.Solution", 1000),
+    ("uavcan.equipment.ahrs.MagneticFieldStrength", 1001),
+    ("uavcan.equipment.ahrs.RawIMU", 1003),
+    (
+        "uavcan.equipment.actuator.ArrayCommand",
+        ArrayCommand::TYPE_ID,
+    ),
+    ("uavcan.equipment.actuator.Status", 1011),
+    ("uavcan.equipment.air_data.StaticPressure", 1028),
+    ("uavcan.equipment.air_data.StaticTemperature", 1029),
+    ("uavcan.equipment.esc.RawCommand", 1030),
+    ("uavcan.equipment.esc.Status", 1034),
+    ("uavcan.equipment.range_sensor.Measurement", 1050),
+    ("uavcan.equipment.gnss.Auxiliary", 1061),
+    ("uavcan.equipment.gnss.Fix2", 1063),
+    ("uavcan.equipment.indication.BeepCommand", 1080),
+    ("uavcan.equipment.indication.LightsCommand", 1081),
+    ("uavcan.equipment.power.BatteryInfo", 1092),
+    ("uavcan.equipment.safety.ArmingStatus", 1100),
+];
+
+/// Default data type ID of a well-known standard message type from its full
+/// DSDL name, such as `uavcan.protocol.NodeStatus`.
+///
+/// Only a selection of message types is known, services are not included as
+/// their IDs are a separate namespace. Returns `None` for other names.
+pub fn name_to_id(name: &str) -> Option<u16> {
+    MESSAGE_TYPES
+        .iter()
+        .find(|(full_name, _)| *full_name == name)
+        .map(|(_, id)| *id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        assert_eq!(name_to_id("uavcan.protocol.NodeStatus"), Some(341));
+        assert_eq!(name_to_id("uavcan.equipment.esc.RawCommand"), Some(1030));
+        assert_eq!(
+            name_to_id("uavcan.protocol.dynamic_node_id.Allocation"),
+            Some(1)
+        );
+
+        assert_eq!(name_to_id("uavcan.protocol.nodestatus"), None);
+        assert_eq!(name_to_id("NodeStatus"), None);
+        assert_eq!(name_to_id(""), None);
+    }
+
+    #[test]
+    fn unique() {
+        for (i, (name, id)) in MESSAGE_TYPES.iter().enumerate() {
+            for (other_name, other_id) in &MESSAGE_TYPES[i + 1..] {
+                assert_ne!(name, other_name);
+                assert_ne!(id, other_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use super::{Health, Mode, NodeStatus};
+
+/// Change observed by [`NodeMonitor::update`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NodeUpdate {
+    /// The node was unknown or had gone silent.
+    Online,
+    /// The uptime of the node decreased since its previous status.
+    Rebooted,
+    /// Status of a known online node.
+    Updated,
+    /// The node reported [`Mode::Offline`] and is no longer online.
+    Offline,
+}
+
+/// Last known state of a node.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    status: NodeStatus,
+    last_seen: u64,
+}
+
+/// Aggregates the [`NodeStatus`] broadcasts of every node on the bus.
+///
+/// Timestamps are in milliseconds from an arbitrary monotonic clock. A node
+/// is considered offline once it hasn't been heard from for the timeout or
+/// reports [`Mode::Offline`].
+#[derive(Debug, Clone)]
+pub struct NodeMonitor {
+    nodes: [Option<Entry>; 128],
+    timeout: u64,
+}
+
+impl NodeMonitor {
+    /// Time after which a silent node is considered offline, as specified for
+    /// `uavcan.protocol.NodeStatus`.
+    pub const OFFLINE_TIMEOUT_MS: u64 = 3000;
+
+    /// Create a monitor which hasn't seen any node.
+    pub fn new() -> Self {
+        Self {
+            nodes: [None; 128],
+            timeout: Self::OFFLINE_TIMEOUT_MS,
+        }
+    }
+
+    /// Set the time after which a silent node is considered offline.
+    ///
+    /// Defaults to [`NodeMonitor::OFFLINE_TIMEOUT_MS`].
+    pub fn with_timeout(mut self, timeout: u64) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Record a status received from `source_node` at `now`.
+    ///
+    /// Returns `None` if `source_node` is not a valid node ID.
+    pub fn update(&mut self, source_node: u8, status: NodeStatus, now: u64) -> Option<NodeUpdate> {
+        if source_node == 0 {
+            return None;
+        }
+
+        let online = self.is_online(source_node, now);
+        let entry = self.nodes.get_mut(source_node as usize)?;
+
+        let update = match entry {
+            _ if status.mode == Mode::Offline => NodeUpdate::Offline,
+            Some(previous) if status.uptime_sec < previous.status.uptime_sec => {
+                NodeUpdate::Rebooted
+            }
+            Some(_) if online => NodeUpdate::Updated,
+            _ => NodeUpdate::Online,
+        };
+
+        *entry = Some(Entry {
+            status,
+            last_seen: now,
+        });
+
+        Some(update)
+    }
+
+    /// Last status received from `node`.
+    pub fn status_of(&self, node: u8) -> Option<&NodeStatus> {
+        self.entry(node).map(|entry| &entry.status)
+    }
+
+    /// Last health reported by `node`.
+    pub fn health_of(&self, node: u8) -> Option<Health> {
+        self.entry(node).map(|entry| entry.status.health)
+    }
+
+    /// Has `node` reported a status within the timeout before `now`?
+    pub fn is_online(&self, node: u8, now: u64) -> bool {
+        self.entry(node).is_some_and(|entry| {
+            entry.status.mode != Mode::Offline && now.saturating_sub(entry.last_seen) < self.timeout
+        })
+    }
+
+    /// Nodes which are online at `now`.
+    pub fn online(&self, now: u64) -> impl Iterator<Item = u8> + '_ {
+        (1..=127).filter(move |node| self.is_online(*node, now))
+    }
+
+    fn entry(&self, node: u8) -> Option<&Entry> {
+        self.nodes.get(node as usize)?.as_ref()
+    }
+}
+
+impl Default for NodeMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(uptime_sec: u32, health: Health) -> NodeStatus {
+        NodeStatus {
+            uptime_sec,
+            health,
+            mode: Mode::Operational,
+            sub_mode: 0,
+            vendor_specific_status_code: 0,
+        }
+    }
+
+    #[test]
+    fn reboot_and_timeout() {
+        let mut monitor = NodeMonitor::new();
+        assert!(!monitor.is_online(10, 0));
+        assert_eq!(monitor.health_of(10), None);
+
+        let update = monitor.update(10, status(100, Health::Ok), 0);
+        assert_eq!(update, Some(NodeUpdate::Online));
+        let update = monitor.update(10, status(101, Health::Warning), 1000);
+        assert_eq!(update, Some(NodeUpdate::Updated));
+        assert_eq!(monitor.health_of(10), Some(Health::Warning));
+        assert!(monitor.is_online(10, 1000));
+
+        // uptime dropped
+        let update = monitor.update(10, status(0, Health::Ok), 2000);
+        assert_eq!(update, Some(NodeUpdate::Rebooted));
+        assert_eq!(monitor.status_of(10).map(|s| s.uptime_sec), Some(0));
+
+        // silent for longer than the timeout
+        assert!(monitor.is_online(10, 4999));
+        assert!(!monitor.is_online(10, 5000));
+        assert_eq!(monitor.online(5000).count(), 0);
+        let update = monitor.update(10, status(5, Health::Ok), 5000);
+        assert_eq!(update, Some(NodeUpdate::Online));
+
+        let update = monitor.update(20, status(5, Health::Ok), 5000);
+        assert_eq!(update, Some(NodeUpdate::Online));
+        assert!(monitor.online(5000).eq([10, 20]));
+    }
+
+    #[test]
+    fn invalid_node() {
+        let mut monitor = NodeMonitor::new();
+        assert_eq!(monitor.update(0, status(1, Health::Ok), 0), None);
+        assert_eq!(monitor.update(128, status(1, Health::Ok), 0), None);
+        assert!(!monitor.is_online(128, 0));
+    }
+
+    #[test]
+    fn offline_mode() {
+        let mut monitor = NodeMonitor::new();
+        let mut offline = status(1, Health::Ok);
+        offline.mode = Mode::Offline;
+
+        assert_eq!(monitor.update(10, offline, 0), Some(NodeUpdate::Offline));
+        assert!(!monitor.is_online(10, 0));
+
+        // back online after being offline
+        let update = monitor.update(10, status(2, Health::Ok), 1000);
+        assert_eq!(update, Some(NodeUpdate::Online));
+        assert!(monitor.is_online(10, 1000));
+
+        offline.uptime_sec = 3;
+        let update = monitor.update(10, offline, 2000);
+        assert_eq!(update, Some(NodeUpdate::Offline));
+        assert_eq!(monitor.online(2000).count(), 0);
+    }
+}
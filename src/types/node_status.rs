@@ -0,0 +1,192 @@
+use crate::dsdl::{BitReader, BitWriter, Error};
+
+/// Abstract node health, `uavcan.protocol.NodeStatus.health`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Health {
+    /// Functioning properly.
+    Ok,
+    /// Able to perform its function but has a critical parameter out of range.
+    Warning,
+    /// Encountered a major failure.
+    Error,
+    /// Suffered a fatal malfunction.
+    Critical,
+}
+
+impl Health {
+    /// Health from its serialized value.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Ok),
+            1 => Some(Self::Warning),
+            2 => Some(Self::Error),
+            3 => Some(Self::Critical),
+            _ => None,
+        }
+    }
+
+    /// Serialized value of the health.
+    pub fn as_u8(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Current node operating mode, `uavcan.protocol.NodeStatus.mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Mode {
+    /// Normal operating mode.
+    Operational,
+    /// Initialization is in progress, this mode is entered immediately after
+    /// startup.
+    Initialization,
+    /// Calibration, self-test or similar.
+    Maintenance,
+    /// Firmware update is in progress.
+    SoftwareUpdate,
+    /// The node is no longer available.
+    Offline,
+}
+
+impl Mode {
+    /// Mode from its serialized value.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Operational),
+            1 => Some(Self::Initialization),
+            2 => Some(Self::Maintenance),
+            3 => Some(Self::SoftwareUpdate),
+            7 => Some(Self::Offline),
+            _ => None,
+        }
+    }
+
+    /// Serialized value of the mode.
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            Self::Operational => 0,
+            Self::Initialization => 1,
+            Self::Maintenance => 2,
+            Self::SoftwareUpdate => 3,
+            Self::Offline => 7,
+        }
+    }
+}
+
+/// Node status broadcast by every node, `uavcan.protocol.NodeStatus`.
+///
+/// [Reference](https://dronecan.github.io/Specification/7._List_of_standard_data_types/#nodestatus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NodeStatus {
+    /// Seconds since the node started.
+    pub uptime_sec: u32,
+    /// Abstract node health.
+    pub health: Health,
+    /// Current operating mode.
+    pub mode: Mode,
+    /// Mode specific sub-mode, currently unused and should be zero.
+    pub sub_mode: u8,
+    /// Vendor defined status code.
+    pub vendor_specific_status_code: u16,
+}
+
+impl NodeStatus {
+    /// Default data type ID.
+    pub const TYPE_ID: u16 = 341;
+
+    /// Data type signature.
+    pub const SIGNATURE: u64 = 0x0F0868D0C1A7C6F1;
+
+    /// Serialized length in bytes.
+    pub const LEN: usize = 7;
+
+    /// Decode from a transfer payload.
+    pub fn decode(payload: &[u8]) -> Result<Self, Error> {
+        let mut reader = BitReader::new(payload);
+
+        Ok(Self {
+            uptime_sec: reader.read_u32(32)?,
+            health: Health::from_u8(reader.read_u8(2)?).ok_or(Error::InvalidValue)?,
+            mode: Mode::from_u8(reader.read_u8(3)?).ok_or(Error::InvalidValue)?,
+            sub_mode: reader.read_u8(3)?,
+            vendor_specific_status_code: reader.read_u16(16)?,
+        })
+    }
+
+    /// Encode into `buf`, returning the number of bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut writer = BitWriter::new(buf);
+
+        writer.write_u32(self.uptime_sec, 32)?;
+        writer.write_u8(self.health.as_u8(), 2)?;
+        writer.write_u8(self.mode.as_u8(), 3)?;
+        writer.write_u8(self.sub_mode, 3)?;
+        writer.write_u16(self.vendor_specific_status_code, 16)?;
+
+        Ok(writer.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn health_values() {
+        for (value, health) in [
+            (0, Health::Ok),
+            (1, Health::Warning),
+            (2, Health::Error),
+            (3, Health::Critical),
+        ] {
+            assert_eq!(Health::from_u8(value), Some(health));
+            assert_eq!(health.as_u8(), value);
+        }
+
+        assert_eq!(Health::from_u8(4), None);
+    }
+
+    #[test]
+    fn mode_values() {
+        for (value, mode) in [
+            (0, Mode::Operational),
+            (1, Mode::Initialization),
+            (2, Mode::Maintenance),
+            (3, Mode::SoftwareUpdate),
+            (7, Mode::Offline),
+        ] {
+            assert_eq!(Mode::from_u8(value), Some(mode));
+            assert_eq!(mode.as_u8(), value);
+        }
+
+        for value in 4..7 {
+            assert_eq!(Mode::from_u8(value), None);
+        }
+    }
+
+    #[test]
+    fn encode_decode() {
+        let status = NodeStatus {
+            uptime_sec: 0x12345678,
+            health: Health::Warning,
+            mode: Mode::Maintenance,
+            sub_mode: 5,
+            vendor_specific_status_code: 0xBEEF,
+        };
+        let payload = [0x78, 0x56, 0x34, 0x12, 0x55, 0xEF, 0xBE];
+
+        let mut buf = [0; NodeStatus::LEN];
+        assert_eq!(status.encode(&mut buf), Ok(NodeStatus::LEN));
+        assert_eq!(buf, payload);
+        assert_eq!(NodeStatus::decode(&payload), Ok(status));
+
+        assert_eq!(status.encode(&mut [0; 6]), Err(Error::BufferTooSmall));
+        assert_eq!(NodeStatus::decode(&payload[..6]), Err(Error::Truncated));
+        assert_eq!(
+            NodeStatus::decode(&[0, 0, 0, 0, 0x20, 0, 0]),
+            Err(Error::InvalidValue)
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{Error, Id};
+
+/// Length of the header preceding the frame data in the wire format.
+pub const WIRE_HEADER_LEN: usize = 5;
+
+/// Maximum frame data length in the wire format, the length of a CAN FD frame.
+const MAX_DATA_LEN: usize = 64;
+
+/// Encode a frame in a compact format for tunneling, returning the number of
+/// bytes written.
+///
+/// The format is the raw identifier as 4 big-endian bytes, 1 byte of data
+/// length and the data. Returns [`Error::FrameTooLong`] if `data` is longer
+/// than a CAN FD frame and [`Error::BufferTooSmall`] if it doesn't fit in
+/// `buf`.
+pub fn encode_wire(id: Id, data: &[u8], buf: &mut [u8]) -> Result<usize, Error> {
+    if data.len() > MAX_DATA_LEN {
+        return Err(Error::FrameTooLong);
+    }
+
+    let len = WIRE_HEADER_LEN + data.len();
+    let buf = buf.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+
+    buf[..4].copy_from_slice(&id.as_raw().to_be_bytes());
+    buf[4] = data.len() as u8;
+    buf[WIRE_HEADER_LEN..].copy_from_slice(data);
+
+    Ok(len)
+}
+
+/// Decode a frame encoded with [`encode_wire`], borrowing its data from `buf`.
+///
+/// Returns [`Error::DataLength`] if `buf` is shorter than the encoded length,
+/// [`Error::FrameTooLong`] if the length exceeds a CAN FD frame and
+/// [`Error::FrameFormat`] if the identifier doesn't fit in 29 bits. Bytes after
+/// the frame are ignored.
+pub fn decode_wire(buf: &[u8]) -> Result<(Id, &[u8]), Error> {
+    let header = buf.get(..WIRE_HEADER_LEN).ok_or(Error::DataLength)?;
+
+    let raw = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    let id = Id::new_checked(raw).ok_or(Error::FrameFormat)?;
+
+    let len = header[4] as usize;
+    if len > MAX_DATA_LEN {
+        return Err(Error::FrameTooLong);
+    }
+
+    let data = buf
+        .get(WIRE_HEADER_LEN..WIRE_HEADER_LEN + len)
+        .ok_or(Error::DataLength)?;
+
+    Ok((id, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let id = Id::new(0x0803F20A);
+        let data = [0x01, 0x98, 0x01, 0x00, 0x68, 0xB5, 0x02, 0x9D];
+
+        let mut buf = [0; 16];
+        assert_eq!(encode_wire(id, &data, &mut buf), Ok(13));
+        assert_eq!(buf[..5], [0x08, 0x03, 0xF2, 0x0A, 0x08]);
+        assert_eq!(decode_wire(&buf), Ok((id, data.as_ref())));
+
+        // empty frames only carry the header
+        assert_eq!(encode_wire(id, &[], &mut buf), Ok(WIRE_HEADER_LEN));
+        assert_eq!(decode_wire(&buf[..5]), Ok((id, [].as_ref())));
+    }
+
+    #[test]
+    fn invalid() {
+        let id = Id::new(0x0803F20A);
+        let mut buf = [0; 80];
+
+        assert_eq!(
+            encode_wire(id, &[0; 8], &mut buf[..12]),
+            Err(Error::BufferTooSmall)
+        );
+        assert_eq!(
+            encode_wire(id, &[0; 65], &mut buf),
+            Err(Error::FrameTooLong)
+        );
+
+        assert_eq!(
+            decode_wire(&[0x08, 0x03, 0xF2, 0x0A]),
+            Err(Error::DataLength)
+        );
+        assert_eq!(
+            decode_wire(&[0x08, 0x03, 0xF2, 0x0A, 0x02, 0xC0]),
+            Err(Error::DataLength)
+        );
+        assert_eq!(decode_wire(&[0x20, 0, 0, 0, 0]), Err(Error::FrameFormat));
+        assert_eq!(
+            decode_wire(&[0x08, 0x03, 0xF2, 0x0A, 65]),
+            Err(Error::FrameTooLong)
+        );
+    }
+}
@@ -0,0 +1,21 @@
+//! Deterministic pseudo-random numbers for tests.
+
+/// xorshift32 generator starting from a fixed seed so failures reproduce.
+pub struct XorShift(u32);
+
+impl XorShift {
+    pub fn new() -> Self {
+        Self(0x2545_F491)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+
+    pub fn next_u8(&mut self) -> u8 {
+        self.next_u32() as u8
+    }
+}